@@ -0,0 +1,114 @@
+//! Compares the nested-loop day 3 approach against `ItemSet` bit operations.
+//!
+//! Run with `cargo bench --bench day03`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aoc2022rs::generate::{badge_groups, rucksacks};
+use aoc2022rs::itemset::ItemSet;
+use aoc2022rs::rng::Rng;
+
+fn get_priority(item: char) -> i32 {
+    ItemSet::priority(item).unwrap() as i32
+}
+
+fn loops_find_shared(container: &str) -> char {
+    let (lhs, rhs) = container.split_at(container.len() / 2);
+    for a in lhs.chars() {
+        for b in rhs.chars() {
+            if a == b {
+                return a;
+            }
+        }
+    }
+    panic!("No shared items between {:?} and {:?}", lhs, rhs);
+}
+
+fn loops_find_shared_chars(lhs: String, rhs: String) -> Vec<char> {
+    let mut shared = Vec::new();
+    for a in lhs.chars() {
+        for b in rhs.chars() {
+            if a == b {
+                shared.push(a);
+            }
+        }
+    }
+    shared.sort();
+    shared.dedup();
+    shared
+}
+
+fn loops_find_badge(group: &[String]) -> char {
+    let mut shared = Vec::new();
+    for i in 1..group.len() {
+        if shared.is_empty() {
+            shared = loops_find_shared_chars(group[i - 1].to_string(), group[i].to_string());
+        } else {
+            shared = loops_find_shared_chars(shared.iter().collect(), group[i].to_string());
+        }
+    }
+    *shared.first().unwrap()
+}
+
+fn itemset_find_shared(container: &str) -> char {
+    let (lhs, rhs) = container.split_at(container.len() / 2);
+    let shared = lhs.parse::<ItemSet>().unwrap() & rhs.parse::<ItemSet>().unwrap();
+    shared.iter().next().unwrap()
+}
+
+fn itemset_find_badge(group: &[String]) -> char {
+    group
+        .iter()
+        .map(|x| x.parse::<ItemSet>().unwrap())
+        .fold(ItemSet::all(), ItemSet::intersection)
+        .iter()
+        .next()
+        .unwrap()
+}
+
+fn bench<F: FnMut() -> i32>(name: &str, runs: u32, mut f: F) -> i32 {
+    let answer = f();
+    let mut total = Duration::ZERO;
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f());
+        total += start.elapsed();
+    }
+    println!("{:<24} {:>12.3?} per run", name, total / runs);
+    answer
+}
+
+fn main() {
+    let mut rng = Rng::new(2022);
+    let rucksacks = rucksacks(&mut rng, 100_000);
+    let groups = badge_groups(&mut rng, 30_000, 3);
+
+    let loops = bench("part a: nested loops", 10, || {
+        rucksacks
+            .iter()
+            .map(|x| get_priority(loops_find_shared(x)))
+            .sum()
+    });
+    let bits = bench("part a: item sets", 10, || {
+        rucksacks
+            .iter()
+            .map(|x| get_priority(itemset_find_shared(x)))
+            .sum()
+    });
+    assert_eq!(loops, bits);
+
+    let loops = bench("part b: nested loops", 10, || {
+        groups
+            .iter()
+            .map(|x| get_priority(loops_find_badge(x)))
+            .sum()
+    });
+    let bits = bench("part b: item sets", 10, || {
+        groups
+            .iter()
+            .map(|x| get_priority(itemset_find_badge(x)))
+            .sum()
+    });
+    assert_eq!(loops, bits);
+}
@@ -0,0 +1,87 @@
+//! Compares three day 5 simulations on generated inputs of growing size:
+//! moving one crate at a time, `apply_moves` moving each block with one
+//! `split_off` and `extend`, and `apply_moves_fast` on treaps, which both
+//! cranes use since reversing a block is as cheap as moving it.
+//!
+//! Run with `cargo bench --bench day05`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aoc2022rs::generate::crate_puzzle;
+use aoc2022rs::rng::Rng;
+use aoc2022rs::year2022::day05::*;
+
+fn bench<F: FnMut() -> State>(name: &str, runs: u32, mut f: F) -> Vec<Crate> {
+    let answer = f().top_items();
+    let mut total = Duration::ZERO;
+    for _ in 0..runs {
+        let start = Instant::now();
+        black_box(f());
+        total += start.elapsed();
+    }
+    println!("{:<40} {:>12.3?} per run", name, total / runs);
+    answer
+}
+
+/// Pops and pushes every crate on its own, the CrateMover 9001 lifting each
+/// block into a buffer first to keep it in order.
+fn per_crate(mut state: State, crane: &dyn Crane) -> State {
+    let in_order = crane.reverses() == Some(false);
+    while let Some(movement) = state.moves.pop_front() {
+        let mut lifted = Vec::new();
+        for _ in 0..movement.count {
+            let item = state.stacks.get_mut(&movement.source).unwrap().pop_back();
+            let item = item.unwrap();
+            if in_order {
+                lifted.push(item);
+            } else {
+                state
+                    .stacks
+                    .get_mut(&movement.target)
+                    .unwrap()
+                    .push_back(item);
+            }
+        }
+        let target = state.stacks.get_mut(&movement.target).unwrap();
+        while let Some(item) = lifted.pop() {
+            target.push_back(item);
+        }
+    }
+    state
+}
+
+fn main() {
+    let mut rng = Rng::new(2022);
+    // Growing stack heights with moves as large as the stacks allow, where
+    // moving crates one by one costs the most.
+    let sizes = [
+        (10, 2_000, 20_000, 1_000),
+        (10, 20_000, 20_000, 10_000),
+        (10, 200_000, 20_000, 100_000),
+        (100, 1_000_000, 200_000, 100_000),
+    ];
+    for (stacks, crates, moves, max_count) in sizes {
+        let state = crate_puzzle(&mut rng, stacks, crates, moves, max_count);
+        println!(
+            "{} stacks, {} crates, {} moves of up to {} crates",
+            stacks, crates, moves, max_count
+        );
+        let runs = if crates > 100_000 { 1 } else { 5 };
+        let cranes: [&dyn Crane; 2] = [&CrateMover9000, &CrateMover9001];
+        for crane in cranes {
+            let name = crane.name();
+            let naive = bench(&format!("{}: per crate", name), runs, || {
+                per_crate(state.clone(), crane)
+            });
+            let slow = bench(&format!("{}: apply_moves", name), runs, || {
+                apply_moves(state.clone(), crane)
+            });
+            assert_eq!(naive, slow);
+            let fast = bench(&format!("{}: apply_moves_fast", name), runs, || {
+                apply_moves_fast(state.clone(), crane)
+            });
+            assert_eq!(slow, fast);
+        }
+    }
+}
@@ -1,77 +1,36 @@
-use aoc2022rs::*;
+use std::io::IsTerminal;
 
-#[derive(Debug, Clone, Copy)]
-struct IdRange {
-    start: i32,
-    stop: i32,
-}
+use aoc2022rs::interval::Interval;
+use aoc2022rs::year2022::day04::*;
+use aoc2022rs::*;
 
-fn parse_input(text: String) -> Vec<(IdRange, IdRange)> {
-    let mut pairs = Vec::new();
-    for line in text.trim().lines() {
-        if line.len() == 0 {
-            continue;
-        }
-        let mut lhs = None;
-        let mut rhs = None;
-        if let Some((pair0, pair1)) = line.split_once(",") {
-            if let Some((start, stop)) = pair0.split_once("-") {
-                lhs = Some(IdRange {
-                    start: start.parse().unwrap(),
-                    stop: stop.parse().unwrap(),
-                });
-            }
-            if let Some((start, stop)) = pair1.split_once("-") {
-                rhs = Some(IdRange {
-                    start: start.parse().unwrap(),
-                    stop: stop.parse().unwrap(),
-                });
-            }
-        }
-        if lhs.is_none() || rhs.is_none() {
-            panic!("Unparse line: {:?}", line);
+fn main() {
+    let mut depth = 1;
+    let mut visualize = false;
+    let mut width = 80;
+    for arg in std::env::args().skip(1) {
+        if arg == "--show" {
+            visualize = true;
+        } else if let Some(value) = arg.strip_prefix("--width=") {
+            width = value.parse().expect("Width must be a number.");
+        } else {
+            depth = arg.parse().expect("Depth must be a number.");
         }
-        pairs.push((lhs.unwrap(), rhs.unwrap()));
     }
-    pairs
-}
-
-fn is_fully_contained(lhs: IdRange, rhs: IdRange) -> bool {
-    (lhs.start >= rhs.start && lhs.stop <= rhs.stop)
-        || (rhs.start >= lhs.start && rhs.stop <= lhs.stop)
-}
-
-fn total_overlap_pairs(pairs: Vec<(IdRange, IdRange)>) -> usize {
-    pairs
-        .iter()
-        .filter(|x| is_fully_contained(x.0, x.1))
-        .count()
-}
-
-fn main() {
-    let pairs = parse_input(load_input(4));
-    let overlaps = total_overlap_pairs(pairs);
-    println!("Answer: {}", overlaps);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn day04a_example1() {
-        let input = "
-2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8
-        "
-        .to_string();
-        let pairs = parse_input(input);
-        assert_eq!(6, pairs.len());
-        let overlaps = total_overlap_pairs(pairs);
-        assert_eq!(2, overlaps);
+    let groups = parse_input(&load_input(4));
+    if visualize {
+        print!(
+            "{}",
+            show(
+                &groups,
+                any_contained,
+                width,
+                std::io::stdout().is_terminal()
+            )
+        );
     }
+    let elves: Vec<Interval> = groups.iter().flatten().copied().collect();
+    println!("{}", report(&elves, depth));
+    let overlaps = total_overlap_pairs(&groups, any_contained);
+    println!("Answer: {}", overlaps);
 }
@@ -0,0 +1,26 @@
+use std::fs::File;
+
+use aoc2022rs::year2022::day06::*;
+use aoc2022rs::*;
+
+fn main() {
+    let mut stdin = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--stdin" {
+            stdin = true;
+        }
+    }
+    let marker = if stdin {
+        read_marker(std::io::stdin().lock(), PACKET_MARKER)
+    } else {
+        let file = File::open(input_path(6)).expect("Could not read input file.");
+        read_marker(file, PACKET_MARKER)
+    };
+    match marker.expect("Could not read the signal.") {
+        Some(position) => println!("Answer: {}", position),
+        None => {
+            eprintln!("No marker in the signal");
+            std::process::exit(1);
+        }
+    }
+}
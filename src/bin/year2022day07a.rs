@@ -0,0 +1,23 @@
+use aoc2022rs::year2022::day07::*;
+use aoc2022rs::*;
+
+fn main() {
+    let mut tree = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--tree" {
+            tree = true;
+        }
+    }
+    let fs = match parse_input(&load_input(7)) {
+        Ok(fs) => fs,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    if tree {
+        print!("{}", fs);
+    }
+    let answer = small_dirs_total(&fs, SMALL_DIR);
+    println!("Answer: {}", answer);
+}
@@ -0,0 +1,29 @@
+use aoc2022rs::year2022::day07::*;
+use aoc2022rs::*;
+
+fn main() {
+    let mut tree = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--tree" {
+            tree = true;
+        }
+    }
+    let fs = match parse_input(&load_input(7)) {
+        Ok(fs) => fs,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    if tree {
+        print!("{}", fs);
+    }
+    let answer = match dir_to_delete(&fs, DISK_SIZE, UPDATE_SIZE) {
+        Some(size) => size,
+        None => {
+            eprintln!("No directory is large enough");
+            std::process::exit(1);
+        }
+    };
+    println!("Answer: {}", answer);
+}
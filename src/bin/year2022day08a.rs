@@ -0,0 +1,12 @@
+use aoc2022rs::year2022::day08::*;
+use aoc2022rs::*;
+
+fn main() {
+    match parse_input(&load_input(8)) {
+        Ok(grid) => println!("Answer: {}", count_visible(&grid)),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
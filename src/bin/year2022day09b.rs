@@ -0,0 +1,5 @@
+use aoc2022rs::year2022::day09::cli;
+
+fn main() {
+    cli(10);
+}
@@ -0,0 +1,12 @@
+use aoc2022rs::year2022::day10::*;
+use aoc2022rs::*;
+
+fn main() {
+    match parse_input(&load_input(10)) {
+        Ok(program) => println!("Answer: {}", signal_strength(&program)),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
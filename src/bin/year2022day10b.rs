@@ -0,0 +1,21 @@
+use aoc2022rs::year2022::day10::*;
+use aoc2022rs::*;
+
+fn main() {
+    let program = match parse_input(&load_input(10)) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let screen = draw_crt(&program);
+    println!("{}", ocr::render(&screen));
+    match ocr::recognize(&screen) {
+        Ok(letters) => println!("Answer: {}", letters),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
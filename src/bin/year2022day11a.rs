@@ -0,0 +1,19 @@
+use aoc2022rs::year2022::day11::*;
+use aoc2022rs::*;
+
+fn main() {
+    let monkeys = match parse_input(&load_input(11)) {
+        Ok(monkeys) => monkeys,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    match simulate(&monkeys, 20, |x| x / 3) {
+        Ok(inspected) => println!("Answer: {}", monkey_business(&inspected)),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use aoc2022rs::year2022::day11::*;
+use aoc2022rs::*;
+
+fn main() {
+    let mut rounds = 10000;
+    for arg in std::env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--rounds=") {
+            rounds = value.parse().expect("Round count must be a number.");
+        }
+    }
+    let monkeys = match parse_input(&load_input(11)) {
+        Ok(monkeys) => monkeys,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let modulus = match common_modulus(&monkeys) {
+        Ok(modulus) => modulus,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    match simulate(&monkeys, rounds, |x| x % modulus) {
+        Ok(inspected) => {
+            for (i, count) in inspected.iter().enumerate() {
+                println!("Monkey {} inspected items {} times.", i, count);
+            }
+            println!("Answer: {}", monkey_business(&inspected));
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
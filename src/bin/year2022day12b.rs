@@ -0,0 +1,30 @@
+use aoc2022rs::year2022::day12::*;
+use aoc2022rs::*;
+
+fn main() {
+    let mut show = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--path" {
+            show = true;
+        }
+    }
+    let map = match parse_input(&load_input(12)) {
+        Ok(map) => map,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    match descend(&map) {
+        Some(path) => {
+            if show {
+                println!("{}", map.render_path(&path));
+            }
+            println!("Answer: {}", path.len() - 1);
+        }
+        None => {
+            eprintln!("No way up to the end");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use aoc2022rs::year2022::day13::*;
+use aoc2022rs::*;
+
+fn main() {
+    match parse_input(&load_input(13)) {
+        Ok(pairs) => println!("Answer: {}", ordered_pairs(&pairs)),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
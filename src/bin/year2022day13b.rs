@@ -0,0 +1,12 @@
+use aoc2022rs::year2022::day13::*;
+use aoc2022rs::*;
+
+fn main() {
+    match parse_packets(&load_input(13)) {
+        Ok(packets) => println!("Answer: {}", decoder_key(&packets)),
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Seeded random inputs shaped like each solved day's puzzle, for property
+//! tests and benchmarks.
+
+use crate::interval::Interval;
+use crate::rng::Rng;
+use crate::year2022::day05::{Movement, State};
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Day 1: `count` elves carrying one to six snacks each.
+pub fn calorie_groups(rng: &mut Rng, count: usize) -> Vec<Vec<i32>> {
+    (0..count)
+        .map(|_| {
+            let snacks = 1 + rng.below(6) as usize;
+            (0..snacks).map(|_| 1 + rng.below(60_000) as i32).collect()
+        })
+        .collect()
+}
+
+pub fn calories_input(groups: &[Vec<i32>]) -> String {
+    let groups: Vec<String> = groups
+        .iter()
+        .map(|x| {
+            let lines: Vec<String> = x.iter().map(|x| x.to_string()).collect();
+            lines.join("\n")
+        })
+        .collect();
+    groups.join("\n\n") + "\n"
+}
+
+/// Day 2: `count` rounds as (`A`-`C`, `X`-`Z`) letter pairs.
+pub fn strategy_guide(rng: &mut Rng, count: usize) -> Vec<(char, char)> {
+    (0..count)
+        .map(|_| {
+            let opponent = (b'A' + rng.below(3) as u8) as char;
+            let player = (b'X' + rng.below(3) as u8) as char;
+            (opponent, player)
+        })
+        .collect()
+}
+
+pub fn strategy_input(rounds: &[(char, char)]) -> String {
+    rounds
+        .iter()
+        .map(|(opponent, player)| format!("{} {}\n", opponent, player))
+        .collect()
+}
+
+fn shuffled_items(rng: &mut Rng) -> Vec<char> {
+    let mut items: Vec<char> = ITEMS.chars().collect();
+    for i in (1..items.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+    items
+}
+
+fn pick(rng: &mut Rng, pool: &[char], count: usize) -> String {
+    (0..count)
+        .map(|_| pool[rng.below(pool.len() as u64) as usize])
+        .collect()
+}
+
+/// Day 3: rucksacks whose compartments share exactly one item.
+pub fn rucksacks(rng: &mut Rng, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let items = shuffled_items(rng);
+            let size = 8 + rng.below(24) as usize;
+            let (lhs_pool, rhs_pool) = items[1..].split_at(25);
+            let mut lhs = pick(rng, lhs_pool, size - 1);
+            let mut rhs = pick(rng, rhs_pool, size - 1);
+            lhs.insert(rng.below(size as u64) as usize, items[0]);
+            rhs.insert(rng.below(size as u64) as usize, items[0]);
+            lhs + &rhs
+        })
+        .collect()
+}
+
+/// Day 3: `count` groups of `size` (at least 2) rucksacks sharing exactly one
+/// badge, each rucksack still splitting into two compartments.
+pub fn badge_groups(rng: &mut Rng, count: usize, size: usize) -> Vec<Vec<String>> {
+    (0..count)
+        .map(|_| {
+            let items = shuffled_items(rng);
+            let parts: Vec<&[char]> = items[1..].chunks(51 / size).collect();
+            (0..size)
+                .map(|i| {
+                    // Each rucksack lacks a different part, so only the
+                    // badge is in all of them.
+                    let pool: Vec<char> = (0..size)
+                        .filter(|j| *j != i)
+                        .flat_map(|j| parts[j].iter().copied())
+                        .collect();
+                    let length = 2 * (8 + rng.below(24) as usize);
+                    let mut rucksack = pick(rng, &pool, length - 1);
+                    rucksack.insert(rng.below(length as u64) as usize, items[0]);
+                    rucksack
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn lines_input(lines: &[String]) -> String {
+    lines.iter().map(|x| format!("{}\n", x)).collect()
+}
+
+/// Day 4: `count` lines of `ranges` section assignments within `1..=99`.
+pub fn range_groups(rng: &mut Rng, count: usize, ranges: usize) -> Vec<Vec<Interval>> {
+    (0..count)
+        .map(|_| {
+            (0..ranges)
+                .map(|_| {
+                    let start = 1 + rng.below(99) as i64;
+                    let end = start + rng.below(100 - start as u64) as i64;
+                    Interval::new(start, end)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn ranges_input(groups: &[Vec<Interval>]) -> String {
+    groups
+        .iter()
+        .map(|x| {
+            let ranges: Vec<String> = x.iter().map(|x| format!("{}-{}", x.start, x.end)).collect();
+            ranges.join(",") + "\n"
+        })
+        .collect()
+}
+
+/// Day 5: `crates` lettered crates spread over `stacks`, then `moves` valid
+/// moves lifting up to `max_count` crates each. Render it with `to_string`.
+pub fn crate_puzzle(
+    rng: &mut Rng,
+    stacks: u32,
+    crates: usize,
+    moves: usize,
+    max_count: u64,
+) -> State {
+    let mut state = State::new();
+    for label in 1..=stacks {
+        state.stacks.insert(label, Default::default());
+    }
+    for _ in 0..crates {
+        let label = 1 + rng.below(stacks as u64) as u32;
+        let name = ((b'A' + rng.below(26) as u8) as char).to_string();
+        state.stacks.get_mut(&label).unwrap().push_back(name);
+    }
+    if crates == 0 {
+        return state;
+    }
+    let mut heights: Vec<u64> = (1..=stacks)
+        .map(|x| state.stacks[&x].len() as u64)
+        .collect();
+    while state.moves.len() < moves {
+        let source = rng.below(stacks as u64) as usize;
+        let target = rng.below(stacks as u64) as usize;
+        if heights[source] == 0 {
+            continue;
+        }
+        let count = 1 + rng.below(heights[source].min(max_count));
+        heights[source] -= count;
+        heights[target] += count;
+        let movement = Movement::new(count as u32, source as u32 + 1, target as u32 + 1);
+        state.moves.push_back(movement);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::itemset::ItemSet;
+    use crate::year2022::day05::{parse_input, validate};
+
+    #[test]
+    fn generate_inputs_are_well_formed() {
+        let mut rng = Rng::new(41);
+        let groups = calorie_groups(&mut rng, 5);
+        assert_eq!(5, calories_input(&groups).split("\n\n").count());
+        assert_eq!(
+            4,
+            strategy_input(&strategy_guide(&mut rng, 4)).lines().count()
+        );
+
+        for rucksack in rucksacks(&mut rng, 100) {
+            let (lhs, rhs) = rucksack.split_at(rucksack.len() / 2);
+            let shared = lhs.parse::<ItemSet>().unwrap() & rhs.parse::<ItemSet>().unwrap();
+            assert_eq!(1, shared.len());
+        }
+        for size in [2, 3, 5] {
+            for group in badge_groups(&mut rng, 20, size) {
+                assert_eq!(size, group.len());
+                let badges = group
+                    .iter()
+                    .map(|x| x.parse::<ItemSet>().unwrap())
+                    .fold(ItemSet::all(), ItemSet::intersection);
+                assert_eq!(1, badges.len());
+                assert!(group.iter().all(|x| x.len().is_multiple_of(2)));
+            }
+        }
+
+        let groups = range_groups(&mut rng, 50, 3);
+        assert!(groups.iter().flatten().all(|x| 1 <= x.start && x.end <= 99));
+        assert!(groups.iter().flatten().all(|x| !x.is_empty()));
+        assert_eq!(50, ranges_input(&groups).lines().count());
+
+        let state = crate_puzzle(&mut rng, 9, 40, 100, 10);
+        assert_eq!(Ok(()), validate(&state, &[]));
+        assert_eq!(state, parse_input(&state.to_string()).unwrap());
+    }
+}
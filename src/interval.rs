@@ -0,0 +1,521 @@
+use std::cmp::{max, min};
+use std::str::FromStr;
+
+/// Inclusive range of integers `start..=end`; empty when `start > end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Interval {
+        Interval { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    /// Number of integers in the interval; `u128` because `i64::MIN..=i64::MAX`
+    /// holds one more than `u64::MAX`.
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            0
+        } else {
+            self.end.abs_diff(self.start) as u128 + 1
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every value of `other` is also in `self`.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let result = Interval::new(max(self.start, other.start), min(self.end, other.end));
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    pub fn union(&self, other: &Interval) -> IntervalSet {
+        [*self, *other].into_iter().collect()
+    }
+
+    pub fn difference(&self, other: &Interval) -> IntervalSet {
+        let mut set: IntervalSet = [*self].into_iter().collect();
+        set.remove(*other);
+        set
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    /// Parses `start-end`, where either bound may itself be negative.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, ch)| *ch == '-')
+            .map(|(index, _)| index);
+        let (start, end) = match split {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => return Err(format!("Not an interval: {:?}", s)),
+        };
+        match (start.trim().parse(), end.trim().parse()) {
+            (Ok(start), Ok(end)) => Ok(Interval::new(start, end)),
+            _ => Err(format!("Not an interval: {:?}", s)),
+        }
+    }
+}
+
+/// Union of intervals, normalized to sorted, disjoint, non-adjacent members.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers covered by the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(|x| x.len()).sum()
+    }
+
+    /// Smallest single interval covering the whole set.
+    pub fn span(&self) -> Option<Interval> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some(Interval::new(first.start, last.end)),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.intervals.partition_point(|x| x.end < value);
+        self.intervals
+            .get(index)
+            .map(|x| x.contains(value))
+            .unwrap_or(false)
+    }
+
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        if other.is_empty() {
+            return true;
+        }
+        let index = self.intervals.partition_point(|x| x.end < other.start);
+        self.intervals
+            .get(index)
+            .map(|x| x.contains_interval(other))
+            .unwrap_or(false)
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        let mut merged = Vec::with_capacity(self.intervals.len() + 1);
+        let mut pending = interval;
+        let mut placed = false;
+        for current in self.intervals.iter() {
+            if current.end.saturating_add(1) < pending.start {
+                merged.push(*current);
+            } else if pending.end.saturating_add(1) < current.start {
+                if !placed {
+                    merged.push(pending);
+                    placed = true;
+                }
+                merged.push(*current);
+            } else {
+                pending = Interval::new(
+                    min(pending.start, current.start),
+                    max(pending.end, current.end),
+                );
+            }
+        }
+        if !placed {
+            merged.push(pending);
+        }
+        self.intervals = merged;
+    }
+
+    pub fn remove(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        let mut remaining = Vec::with_capacity(self.intervals.len() + 1);
+        for current in self.intervals.iter() {
+            if !current.overlaps(&interval) {
+                remaining.push(*current);
+                continue;
+            }
+            if current.start < interval.start {
+                remaining.push(Interval::new(current.start, interval.start - 1));
+            }
+            if interval.end < current.end {
+                remaining.push(Interval::new(interval.end + 1, current.end));
+            }
+        }
+        self.intervals = remaining;
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (lhs, rhs) = (self.intervals[i], other.intervals[j]);
+            if let Some(shared) = lhs.intersection(&rhs) {
+                result.push(shared);
+            }
+            if lhs.end < rhs.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.remove(*interval);
+        }
+        result
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut sorted: Vec<Interval> = iter.into_iter().filter(|x| !x.is_empty()).collect();
+        sorted.sort();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end.saturating_add(1) => {
+                    last.end = max(last.end, interval.end);
+                }
+                _ => intervals.push(interval),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+/// Maximal segments where the number of overlapping intervals is constant and
+/// non-zero, as (segment, depth) pairs in order, found with a sweep line.
+pub fn coverage(intervals: &[Interval]) -> Vec<(Interval, usize)> {
+    let mut events: Vec<(i64, isize)> = Vec::with_capacity(intervals.len() * 2);
+    for interval in intervals.iter().filter(|x| !x.is_empty()) {
+        events.push((interval.start, 1));
+        if let Some(after) = interval.end.checked_add(1) {
+            events.push((after, -1));
+        }
+    }
+    events.sort();
+    let mut segments = Vec::new();
+    let mut depth: isize = 0;
+    let mut index = 0;
+    while index < events.len() {
+        let position = events[index].0;
+        while index < events.len() && events[index].0 == position {
+            depth += events[index].1;
+            index += 1;
+        }
+        if depth > 0 {
+            let end = match events.get(index) {
+                Some((next, _)) => next - 1,
+                None => i64::MAX,
+            };
+            segments.push((Interval::new(position, end), depth as usize));
+        }
+    }
+    segments
+}
+
+/// Values covered by at least `count` of the intervals.
+pub fn covered_at_least(intervals: &[Interval], count: usize) -> IntervalSet {
+    coverage(intervals)
+        .into_iter()
+        .filter(|(_, depth)| *depth >= count.max(1))
+        .map(|(segment, _)| segment)
+        .collect()
+}
+
+/// Indices of intervals fully covered by some other single interval.
+///
+/// Of several identical intervals only the first is kept, so dropping every
+/// reported index leaves the covered values unchanged.
+pub fn redundant(intervals: &[Interval]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|i| (intervals[*i].start, -intervals[*i].end, *i));
+    let mut furthest: Option<i64> = None;
+    let mut result = Vec::new();
+    for index in order {
+        let interval = intervals[index];
+        match furthest {
+            Some(end) if interval.end <= end => result.push(index),
+            _ => furthest = Some(interval.end),
+        }
+    }
+    result.sort();
+    result
+}
+
+/// Values between the lowest and highest interval bound that no interval
+/// covers.
+pub fn uncovered(intervals: &[Interval]) -> IntervalSet {
+    let covered: IntervalSet = intervals.iter().copied().collect();
+    match covered.span() {
+        Some(span) => [span]
+            .into_iter()
+            .collect::<IntervalSet>()
+            .difference(&covered),
+        None => IntervalSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use std::collections::BTreeSet;
+
+    const LOW: i64 = -20;
+    const HIGH: i64 = 20;
+
+    fn random_interval(rng: &mut Rng) -> Interval {
+        let start = LOW + rng.below((HIGH - LOW) as u64) as i64;
+        let end = start - 1 + rng.below(12) as i64;
+        Interval::new(start, end)
+    }
+
+    fn random_set(rng: &mut Rng) -> (IntervalSet, BTreeSet<i64>) {
+        let intervals: Vec<Interval> = (0..rng.below(6)).map(|_| random_interval(rng)).collect();
+        let members = intervals.iter().flat_map(|x| x.start..=x.end).collect();
+        (intervals.into_iter().collect(), members)
+    }
+
+    fn members(set: &IntervalSet) -> BTreeSet<i64> {
+        set.iter().flat_map(|x| x.start..=x.end).collect()
+    }
+
+    fn assert_normalized(set: &IntervalSet) {
+        for interval in set.iter() {
+            assert!(!interval.is_empty(), "{:?}", set);
+        }
+        for pair in set.intervals().windows(2) {
+            assert!(pair[0].end + 1 < pair[1].start, "{:?}", set);
+        }
+    }
+
+    #[test]
+    fn interval_basics() {
+        let a: Interval = "2-8".parse().unwrap();
+        let b: Interval = "-3--1".parse().unwrap();
+        assert_eq!(Interval::new(2, 8), a);
+        assert_eq!(Interval::new(-3, -1), b);
+        assert!("2".parse::<Interval>().is_err());
+        assert!("a-b".parse::<Interval>().is_err());
+        assert_eq!(7, a.len());
+        assert_eq!(0, Interval::new(1, 0).len());
+        assert_eq!(1 << 64, Interval::new(i64::MIN, i64::MAX).len());
+        assert_eq!(1, Interval::new(i64::MAX, i64::MAX).len());
+        let full: IntervalSet = [Interval::new(i64::MIN, -1), Interval::new(0, i64::MAX)]
+            .into_iter()
+            .collect();
+        assert_eq!(1 << 64, full.len());
+        assert!(a.contains_interval(&Interval::new(3, 7)));
+        assert!(!Interval::new(3, 7).contains_interval(&a));
+        assert_eq!(
+            Some(Interval::new(6, 8)),
+            a.intersection(&Interval::new(6, 9))
+        );
+        assert_eq!(None, a.intersection(&b));
+        assert_eq!(
+            vec![Interval::new(-3, -1), Interval::new(2, 8)],
+            a.union(&b).intervals()
+        );
+        assert_eq!(
+            vec![Interval::new(0, 8)],
+            a.union(&Interval::new(0, 1)).intervals()
+        );
+        assert_eq!(
+            vec![Interval::new(2, 3), Interval::new(6, 8)],
+            a.difference(&Interval::new(4, 5)).intervals()
+        );
+        assert_eq!(Some(Interval::new(-3, 8)), a.union(&b).span());
+    }
+
+    #[test]
+    fn interval_sweep() {
+        let intervals = vec![
+            Interval::new(2, 4),
+            Interval::new(6, 8),
+            Interval::new(3, 7),
+            Interval::new(6, 8),
+            Interval::new(12, 12),
+        ];
+        assert_eq!(
+            vec![
+                (Interval::new(2, 2), 1),
+                (Interval::new(3, 4), 2),
+                (Interval::new(5, 5), 1),
+                (Interval::new(6, 7), 3),
+                (Interval::new(8, 8), 2),
+                (Interval::new(12, 12), 1),
+            ],
+            coverage(&intervals)
+        );
+        assert_eq!(
+            vec![Interval::new(3, 4), Interval::new(6, 8)],
+            covered_at_least(&intervals, 2).intervals()
+        );
+        assert_eq!(
+            vec![Interval::new(6, 7)],
+            covered_at_least(&intervals, 3).intervals()
+        );
+        assert_eq!(vec![3], redundant(&intervals));
+        assert_eq!(
+            vec![Interval::new(9, 11)],
+            uncovered(&intervals).intervals()
+        );
+        assert!(coverage(&[]).is_empty());
+        assert!(uncovered(&[]).is_empty());
+    }
+
+    #[test]
+    fn interval_sweep_matches_brute_force() {
+        let mut rng = Rng::new(31);
+        for _ in 0..500 {
+            let intervals: Vec<Interval> = (0..rng.below(8))
+                .map(|_| random_interval(&mut rng))
+                .filter(|x| !x.is_empty())
+                .collect();
+            let depth = |value: i64| intervals.iter().filter(|x| x.contains(value)).count();
+            let lowest = intervals.iter().map(|x| x.start).min();
+            let highest = intervals.iter().map(|x| x.end).max();
+            for count in 1..4 {
+                let deep = covered_at_least(&intervals, count);
+                for value in LOW - 2..HIGH + 14 {
+                    assert_eq!(depth(value) >= count, deep.contains(value));
+                }
+            }
+            let gaps = uncovered(&intervals);
+            for value in LOW - 2..HIGH + 14 {
+                let inside =
+                    lowest.is_some_and(|x| x <= value) && highest.is_some_and(|x| value <= x);
+                assert_eq!(inside && depth(value) == 0, gaps.contains(value));
+            }
+            let flagged = redundant(&intervals);
+            let mut kept: Vec<Interval> = Vec::new();
+            for (index, interval) in intervals.iter().enumerate() {
+                let covered = intervals
+                    .iter()
+                    .enumerate()
+                    .any(|(other, x)| other != index && x.contains_interval(interval));
+                if !covered {
+                    assert!(!flagged.contains(&index));
+                }
+                if !flagged.contains(&index) {
+                    kept.push(*interval);
+                }
+            }
+            assert_eq!(
+                intervals.iter().copied().collect::<IntervalSet>(),
+                kept.iter().copied().collect()
+            );
+            for (index, interval) in kept.iter().enumerate() {
+                assert!(!kept
+                    .iter()
+                    .enumerate()
+                    .any(|(other, x)| other != index && x.contains_interval(interval)));
+            }
+        }
+    }
+
+    #[test]
+    fn interval_matches_brute_force() {
+        let mut rng = Rng::new(30);
+        for _ in 0..2000 {
+            let a = random_interval(&mut rng);
+            let b = random_interval(&mut rng);
+            let (lhs, lhs_members) = random_set(&mut rng);
+            let (rhs, rhs_members) = random_set(&mut rng);
+            assert_normalized(&lhs);
+            assert_eq!(lhs_members, members(&lhs));
+            assert_eq!(lhs_members.len() as u128, lhs.len());
+
+            let union = lhs.union(&rhs);
+            let intersection = lhs.intersection(&rhs);
+            let difference = lhs.difference(&rhs);
+            for set in [&union, &intersection, &difference] {
+                assert_normalized(set);
+            }
+            assert_eq!(&lhs_members | &rhs_members, members(&union));
+            assert_eq!(&lhs_members & &rhs_members, members(&intersection));
+            assert_eq!(&lhs_members - &rhs_members, members(&difference));
+
+            let mut inserted = lhs.clone();
+            inserted.insert(a);
+            let mut removed = lhs.clone();
+            removed.remove(a);
+            assert_normalized(&inserted);
+            assert_normalized(&removed);
+            let a_members: BTreeSet<i64> = (a.start..=a.end).collect();
+            let b_members: BTreeSet<i64> = (b.start..=b.end).collect();
+            assert_eq!(&lhs_members | &a_members, members(&inserted));
+            assert_eq!(&lhs_members - &a_members, members(&removed));
+            assert_eq!(a_members.is_subset(&lhs_members), lhs.contains_interval(&a));
+
+            assert_eq!(a_members.len() as u128, a.len());
+            assert_eq!(b_members.is_subset(&a_members), a.contains_interval(&b));
+            assert_eq!(!a_members.is_disjoint(&b_members), a.overlaps(&b));
+            let shared: BTreeSet<i64> = a
+                .intersection(&b)
+                .iter()
+                .flat_map(|x| x.start..=x.end)
+                .collect();
+            assert_eq!(&a_members & &b_members, shared);
+            assert_eq!(&a_members | &b_members, members(&a.union(&b)));
+            assert_eq!(&a_members - &b_members, members(&a.difference(&b)));
+
+            for value in LOW - 2..HIGH + 14 {
+                assert_eq!(lhs_members.contains(&value), lhs.contains(value));
+                assert_eq!(a_members.contains(&value), a.contains(value));
+            }
+        }
+    }
+}
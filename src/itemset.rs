@@ -0,0 +1,172 @@
+use std::ops::{BitAnd, BitOr};
+use std::str::FromStr;
+
+/// Bit for each ASCII byte that is a rucksack item, zero for anything else.
+const ITEM_BITS: [u64; 256] = {
+    let mut bits = [0; 256];
+    let mut i = 0;
+    while i < 26 {
+        bits[b'a' as usize + i] = 1 << (i + 1);
+        bits[b'A' as usize + i] = 1 << (i + 27);
+        i += 1;
+    }
+    bits
+};
+
+/// Set of rucksack items, one bit per priority (`a`-`z` are 1-26, `A`-`Z` are
+/// 27-52), so intersecting any number of rucksacks is a chain of `&`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn new() -> ItemSet {
+        ItemSet(0)
+    }
+
+    /// Set holding every known item, the identity for `intersection`.
+    pub fn all() -> ItemSet {
+        ItemSet(((1u64 << 52) - 1) << 1)
+    }
+
+    pub fn priority(item: char) -> Option<u32> {
+        if item.is_ascii_lowercase() {
+            Some(item as u32 - 'a' as u32 + 1)
+        } else if item.is_ascii_uppercase() {
+            Some(item as u32 - 'A' as u32 + 27)
+        } else {
+            None
+        }
+    }
+
+    pub fn item(priority: u32) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority - 1),
+            27..=52 => char::from_u32('A' as u32 + priority - 27),
+            _ => None,
+        }
+    }
+
+    /// Adds `item`, returning false if it is not a valid rucksack item.
+    pub fn insert(&mut self, item: char) -> bool {
+        match ItemSet::priority(item) {
+            Some(priority) => {
+                self.0 |= 1 << priority;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        match ItemSet::priority(item) {
+            Some(priority) => self.0 & (1 << priority) != 0,
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    /// Priorities of the items in ascending order.
+    pub fn priorities(&self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let priority = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(priority)
+        })
+    }
+
+    /// Items in ascending priority order.
+    pub fn iter(&self) -> impl Iterator<Item = char> {
+        self.priorities().map(|x| ItemSet::item(x).unwrap())
+    }
+}
+
+impl FromStr for ItemSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bits = 0u64;
+        for (index, item) in s.bytes().enumerate() {
+            let bit = ITEM_BITS[item as usize];
+            if bit == 0 {
+                let item = s[index..].chars().next().unwrap();
+                return Err(format!("Not a rucksack item: {:?}", item));
+            }
+            bits |= bit;
+        }
+        Ok(ItemSet(bits))
+    }
+}
+
+impl FromIterator<ItemSet> for ItemSet {
+    /// Union of all the sets.
+    fn from_iter<I: IntoIterator<Item = ItemSet>>(iter: I) -> Self {
+        iter.into_iter().fold(ItemSet::new(), ItemSet::union)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: ItemSet) -> ItemSet {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: ItemSet) -> ItemSet {
+        self.union(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn itemset_priorities() {
+        for (item, priority) in [('a', 1), ('z', 26), ('A', 27), ('Z', 52)] {
+            assert_eq!(Some(priority), ItemSet::priority(item));
+            assert_eq!(Some(item), ItemSet::item(priority));
+        }
+        assert_eq!(None, ItemSet::priority('!'));
+        assert_eq!(None, ItemSet::item(0));
+        assert_eq!(None, ItemSet::item(53));
+        assert_eq!(52, ItemSet::all().len());
+    }
+
+    #[test]
+    fn itemset_operations() {
+        let lhs: ItemSet = "vJrwpWtwJgWr".parse().unwrap();
+        let rhs: ItemSet = "hcsFMMfFFhFp".parse().unwrap();
+        assert_eq!(vec!['p'], (lhs & rhs).iter().collect::<Vec<_>>());
+        assert_eq!(vec![16], (lhs & rhs).priorities().collect::<Vec<_>>());
+        let both = lhs | rhs;
+        assert_eq!("cfghprstvwFJMW", both.iter().collect::<String>());
+        assert!(both.contains('W') && !both.contains('x') && !both.contains('1'));
+        assert_eq!(both, [lhs, rhs].into_iter().collect());
+        assert_eq!(lhs, lhs & ItemSet::all());
+        assert!(ItemSet::new().is_empty());
+        assert!("ab1".parse::<ItemSet>().is_err());
+    }
+}
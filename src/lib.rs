@@ -1,3 +1,278 @@
+#[cfg(any(test, feature = "testing"))]
+pub mod generate;
+pub mod interval;
+pub mod itemset;
+pub mod math;
+pub mod ocr;
+#[cfg(any(test, feature = "testing"))]
+pub mod prop;
+pub mod rng;
+pub mod search;
+pub mod solution;
+pub mod window;
+pub mod year2022;
+
 pub fn load_input(day: isize) -> String {
-    std::fs::read_to_string(format!("inputs/{day:0>2}.txt")).expect("Could not read input file.")
+    read_input(day).expect("Could not read input file.")
+}
+
+/// The input for `day`, or an error naming the missing file.
+pub fn read_input(day: isize) -> Result<String, String> {
+    let path = input_path(day);
+    std::fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path, e))
+}
+
+pub fn input_path(day: isize) -> String {
+    format!("inputs/{day:0>2}.txt")
+}
+
+/// A step on a grid, with `Up` toward row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Column and row change of one step.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// Rectangular grid stored row by row, indexed by `(x, y)` with `(0, 0)` at the
+/// top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Result<Grid<T>, String> {
+        if width == 0 || !cells.len().is_multiple_of(width) {
+            return Err(format!(
+                "Cannot split {} cells into rows of {}",
+                cells.len(),
+                width
+            ));
+        }
+        let height = cells.len() / width;
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, one row per non-empty line, converting each
+    /// character with `cell`. Every row must have the same width.
+    pub fn parse<F>(text: &str, mut cell: F) -> Result<Grid<T>, String>
+    where
+        F: FnMut(char) -> Result<T, String>,
+    {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let count = line.chars().count();
+            match width {
+                None => width = Some(count),
+                Some(width) if width != count => {
+                    return Err(format!(
+                        "line {}: row has {} cells, expected {}",
+                        i + 1,
+                        count,
+                        width
+                    ));
+                }
+                Some(_) => {}
+            }
+            for c in line.chars() {
+                cells.push(cell(c).map_err(|e| format!("line {}: {}", i + 1, e))?);
+            }
+        }
+        match width {
+            Some(width) => Grid::from_cells(width, cells),
+            None => Err("Empty grid".to_string()),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// The position one step away, if it is still on the grid.
+    pub fn step(&self, (x, y): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.offset();
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// Positions walking from `start` (excluded) toward the edge.
+    pub fn ray(
+        &self,
+        start: (usize, usize),
+        direction: Direction,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.step(start, direction), move |x| {
+            self.step(*x, direction)
+        })
+    }
+
+    /// Cells of row `y`, left to right.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> + '_ {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    /// Cells of column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    /// Every position in row order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// Positions on the edge facing `direction`, for scanning lines inward.
+    pub fn edge(&self, direction: Direction) -> Vec<(usize, usize)> {
+        match direction {
+            Direction::Up => (0..self.width).map(|x| (x, 0)).collect(),
+            Direction::Down => (0..self.width).map(|x| (x, self.height - 1)).collect(),
+            Direction::Left => (0..self.height).map(|y| (0, y)).collect(),
+            Direction::Right => (0..self.height).map(|y| (self.width - 1, y)).collect(),
+        }
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width, "column {} out of {}", x, self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width, "column {} out of {}", x, self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_parse_and_iterate() {
+        let grid = Grid::parse("123\n456\n", |c| Ok(c.to_digit(10).unwrap())).unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(2, 1)]);
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(vec![4, 5, 6], grid.row(1).copied().collect::<Vec<_>>());
+        assert_eq!(vec![2, 5], grid.column(1).copied().collect::<Vec<_>>());
+        assert_eq!(
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)],
+            grid.positions().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![(0, 1), (1, 1), (2, 1)], grid.edge(Direction::Down));
+        assert_eq!(12, grid.map(|x| x * 2)[(2, 1)]);
+
+        assert_eq!(
+            Err("line 2: row has 2 cells, expected 3".to_string()),
+            Grid::parse("123\n45", Ok)
+        );
+        assert_eq!(
+            Err("line 1: bad x".to_string()),
+            Grid::<u32>::parse("1x", |c| c.to_digit(10).ok_or(format!("bad {}", c)))
+        );
+        assert!(Grid::<char>::parse("\n", Ok).is_err());
+        assert!(Grid::from_cells(2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn grid_rays() {
+        let grid = Grid::filled(4, 3, '.');
+        let ray = |direction| grid.ray((1, 1), direction).collect::<Vec<_>>();
+        assert_eq!(vec![(1, 0)], ray(Direction::Up));
+        assert_eq!(vec![(1, 2)], ray(Direction::Down));
+        assert_eq!(vec![(0, 1)], ray(Direction::Left));
+        assert_eq!(vec![(2, 1), (3, 1)], ray(Direction::Right));
+        assert_eq!(0, grid.ray((0, 0), Direction::Left).count());
+        for direction in Direction::ALL {
+            assert_eq!(direction, direction.opposite().opposite());
+            assert_ne!(direction, direction.opposite());
+        }
+    }
 }
@@ -1,3 +1,126 @@
+//! Runs every registered implementation of each puzzle part against the real
+//! inputs, checking that they agree.
+//!
+//! Usage: `aoc2022rs [list | run | bench] [DAY [PART]] [--runs=N]`
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use aoc2022rs::read_input;
+use aoc2022rs::solution::{agree, by_part, select, Solution};
+
+const YEAR: u32 = 2022;
+
+fn label(solution: &Solution) -> String {
+    format!("{} day {}{}", solution.year, solution.day, solution.part)
+}
+
+fn list(solutions: &[Solution]) {
+    for part in by_part(solutions) {
+        let names: Vec<&str> = part.iter().map(|x| x.name).collect();
+        println!("{}: {}", label(&part[0]), names.join(", "));
+    }
+}
+
+/// Runs each implementation once, returning false if they disagree.
+fn run(part: &[Solution], input: &str) -> bool {
+    let mut answers = Vec::new();
+    for solution in part {
+        let (answer, elapsed) = solution.timed(input);
+        let shown = match &answer {
+            Ok(answer) => answer.clone(),
+            Err(error) => format!("error: {}", error),
+        };
+        println!(
+            "{:<14} {:<10} {:>12.3?}  {}",
+            label(solution),
+            solution.name,
+            elapsed,
+            shown
+        );
+        answers.push((solution, answer));
+    }
+    match agree(&answers) {
+        Ok(_) => true,
+        Err(error) => {
+            eprintln!("Implementations disagree on {}: {}", label(&part[0]), error);
+            false
+        }
+    }
+}
+
+/// Times each implementation over `runs` runs, relative to the first one.
+fn bench(part: &[Solution], input: &str, runs: u32) -> bool {
+    let mut answers = Vec::new();
+    let mut reference = None;
+    for solution in part {
+        let (answer, mut total) = solution.timed(input);
+        for _ in 1..runs {
+            let (_, elapsed) = black_box(solution.timed(black_box(input)));
+            total += elapsed;
+        }
+        let mean = total / runs;
+        let base: Duration = *reference.get_or_insert(mean);
+        println!(
+            "{:<14} {:<10} {:>12.3?} per run  {:>6.2}x",
+            label(solution),
+            solution.name,
+            mean,
+            mean.as_secs_f64() / base.as_secs_f64().max(f64::MIN_POSITIVE)
+        );
+        answers.push((solution, answer));
+    }
+    match agree(&answers) {
+        Ok(_) => true,
+        Err(error) => {
+            eprintln!("Implementations disagree on {}: {}", label(&part[0]), error);
+            false
+        }
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let mut command = None;
+    let mut day = None;
+    let mut part = None;
+    let mut runs = 10;
+    for arg in std::env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--runs=") {
+            runs = value.parse().expect("Runs must be a number.");
+        } else if command.is_none() && ["list", "run", "bench"].contains(&arg.as_str()) {
+            command = Some(arg);
+        } else if day.is_none() {
+            day = Some(arg.parse().expect("Day must be a number."));
+        } else {
+            part = Some(arg.parse().expect("Part must be a letter."));
+        }
+    }
+    let solutions = select(YEAR, day, part);
+    if solutions.is_empty() {
+        eprintln!("No solutions registered for that day and part");
+        std::process::exit(1);
+    }
+    let command = command.unwrap_or("run".to_string());
+    if command == "list" {
+        list(&solutions);
+        return;
+    }
+    let mut agreed = true;
+    for part in by_part(&solutions) {
+        let input = match read_input(part[0].day as isize) {
+            Ok(input) => input,
+            Err(error) => {
+                eprintln!("Skipping {}: {}", label(&part[0]), error);
+                continue;
+            }
+        };
+        agreed &= if command == "bench" {
+            bench(part, &input, runs.max(1))
+        } else {
+            run(part, &input)
+        };
+    }
+    if !agreed {
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,44 @@
+/// Greatest common divisor, with `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, zero if either value is zero, or `None` when it
+/// does not fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        Some(0)
+    } else {
+        (a / gcd(a, b)).checked_mul(b)
+    }
+}
+
+/// Least common multiple of every value, 1 for none.
+pub fn lcm_all<I: IntoIterator<Item = u64>>(values: I) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_gcd_lcm() {
+        assert_eq!(6, gcd(12, 18));
+        assert_eq!(6, gcd(18, 12));
+        assert_eq!(1, gcd(17, 5));
+        assert_eq!(7, gcd(0, 7));
+        assert_eq!(0, gcd(0, 0));
+        assert_eq!(Some(36), lcm(12, 18));
+        assert_eq!(Some(0), lcm(0, 5));
+        assert_eq!(Some(u64::MAX), lcm(u64::MAX, u64::MAX));
+        assert_eq!(None, lcm(u64::MAX, 2));
+        assert_eq!(Some(96577), lcm_all([23, 19, 13, 17]));
+        assert_eq!(Some(12), lcm_all([2, 4, 6, 3]));
+        assert_eq!(Some(1), lcm_all([]));
+        assert_eq!(None, lcm_all([u64::MAX / 2, 3, 5]));
+    }
+}
@@ -0,0 +1,261 @@
+//! Reads the block capitals puzzles draw on pixel grids.
+
+use crate::Grid;
+
+/// The two letter sizes puzzles draw with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    /// 4 pixels wide and 6 tall, one blank column between letters, except
+    /// for `Y` which fills all 5 columns.
+    Small,
+    /// 6 pixels wide and 10 tall, two blank columns between letters.
+    Large,
+}
+
+#[rustfmt::skip]
+const SMALL: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+impl Font {
+    pub fn height(self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    /// Columns from the start of one letter to the start of the next.
+    pub fn advance(self) -> usize {
+        match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        }
+    }
+
+    /// Rows of a letter, `#` for lit pixels, or `None` if the font lacks it.
+    pub fn glyph(self, letter: char) -> Option<Vec<&'static str>> {
+        match self {
+            Font::Small => SMALL.iter().find(|x| x.0 == letter).map(|x| x.1.to_vec()),
+            Font::Large => LARGE.iter().find(|x| x.0 == letter).map(|x| x.1.to_vec()),
+        }
+    }
+
+    fn letters(self) -> Vec<(char, Vec<&'static str>)> {
+        match self {
+            Font::Small => SMALL.iter().map(|x| (x.0, x.1.to_vec())).collect(),
+            Font::Large => LARGE.iter().map(|x| (x.0, x.1.to_vec())).collect(),
+        }
+    }
+}
+
+/// Rows of `#` and `.` for the lit and dark pixels.
+pub fn render(pixels: &Grid<bool>) -> String {
+    let mut text = String::new();
+    for y in 0..pixels.height() {
+        text.extend(pixels.row(y).map(|x| if *x { '#' } else { '.' }));
+        text.push('\n');
+    }
+    text
+}
+
+/// Draws `text` the way puzzles do, each letter `advance` columns after the
+/// previous one.
+pub fn draw(text: &str, font: Font) -> Result<Grid<bool>, String> {
+    let width = (text.chars().count() * font.advance()).max(1);
+    let mut pixels = Grid::filled(width, font.height(), false);
+    for (i, letter) in text.chars().enumerate() {
+        let glyph = font
+            .glyph(letter)
+            .ok_or(format!("No {:?} in the {:?} font", letter, font))?;
+        for (y, row) in glyph.iter().enumerate() {
+            for (x, pixel) in row.chars().enumerate() {
+                pixels[(i * font.advance() + x, y)] = pixel == '#';
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+/// Letters drawn on `pixels` in either font, split wherever a whole column is
+/// dark and compared with each glyph after trimming its dark edges.
+///
+/// A letter as wide as the font's advance touches the next one, so a piece
+/// matching no glyph is tried again as such a letter and whatever follows.
+pub fn recognize(pixels: &Grid<bool>) -> Result<String, String> {
+    let lit_rows: Vec<usize> = (0..pixels.height())
+        .filter(|y| pixels.row(*y).any(|x| *x))
+        .collect();
+    let (top, bottom) = match (lit_rows.first(), lit_rows.last()) {
+        (Some(top), Some(bottom)) => (*top, *bottom),
+        _ => return Ok(String::new()),
+    };
+    let height = bottom - top + 1;
+    let font = match height {
+        6 => Font::Small,
+        10 => Font::Large,
+        _ => return Err(format!("No font is {} pixels tall", height)),
+    };
+    let lit_column = |x: usize| (top..=bottom).any(|y| pixels[(x, y)]);
+    let mut text = String::new();
+    let mut x = 0;
+    while x < pixels.width() {
+        if !lit_column(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < pixels.width() && lit_column(x) {
+            x += 1;
+        }
+        let shape = |end: usize| -> Vec<String> {
+            (top..=bottom)
+                .map(|y| {
+                    (start..end)
+                        .map(|x| if pixels[(x, y)] { '#' } else { '.' })
+                        .collect()
+                })
+                .collect()
+        };
+        let find = |shape: &[String]| {
+            font.letters()
+                .into_iter()
+                .find(|(_, glyph)| trim(glyph) == shape)
+                .map(|x| x.0)
+        };
+        let mut letter = find(&shape(x));
+        let wide = start + font.advance();
+        if letter.is_none() && wide < x {
+            letter = find(&shape(wide));
+            if letter.is_some() {
+                x = wide;
+            }
+        }
+        let letter = letter.ok_or_else(|| {
+            format!(
+                "Unknown letter at column {}:\n{}\n",
+                start,
+                shape(x).join("\n")
+            )
+        })?;
+        text.push(letter);
+    }
+    Ok(text)
+}
+
+/// Glyph rows without the dark columns on either side.
+fn trim(glyph: &[&str]) -> Vec<String> {
+    let lit = |x: usize| glyph.iter().any(|row| row.as_bytes()[x] == b'#');
+    let width = glyph[0].len();
+    let start = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let end = (0..width).rfind(|x| lit(*x)).map_or(0, |x| x + 1);
+    glyph
+        .iter()
+        .map(|row| row[start..end].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ocr_round_trip() {
+        for font in [Font::Small, Font::Large] {
+            let letters: String = font.letters().iter().map(|x| x.0).collect();
+            let pixels = draw(&letters, font).unwrap();
+            assert_eq!(Ok(letters), recognize(&pixels));
+            for (letter, glyph) in font.letters() {
+                assert_eq!(font.height(), glyph.len());
+                assert!(glyph.iter().all(|x| x.len() <= font.advance()));
+                // Every letter must still be one piece once split on dark
+                // columns.
+                assert_eq!(
+                    Ok(letter.to_string()),
+                    recognize(&draw(&letter.to_string(), font).unwrap())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ocr_crt_screen() {
+        // A 40x6 CRT as the puzzle draws it, written out by hand rather than
+        // with `draw`; every Y runs straight into the letter after it.
+        let screen = "\
+###..#...#####.#..#...##.#...#####.#...#
+#..#.#...#...#.#..#....#.#...##....#...#
+#..#..#.#...#..####....#..#.#.###...#.#.
+###....#...#...#..#....#...#..#......#..
+#.#....#..#....#..#.#..#...#..#......#..
+#..#...#..####.#..#..##....#..####...#..
+";
+        let pixels = Grid::parse(screen.trim(), |c| Ok(c == '#')).unwrap();
+        assert_eq!((40, 6), (pixels.width(), pixels.height()));
+        assert_eq!(Ok("RYZHJYEY".to_string()), recognize(&pixels));
+        assert_eq!(screen, render(&draw("RYZHJYEY", Font::Small).unwrap()));
+    }
+
+    #[test]
+    fn ocr_render_and_errors() {
+        let pixels = draw("HI", Font::Small).unwrap();
+        let expected = "\
+#..#..###.
+#..#...#..
+####...#..
+#..#...#..
+#..#...#..
+#..#..###.
+";
+        assert_eq!(expected, render(&pixels));
+        assert!(draw("Q", Font::Small).is_err());
+        assert_eq!(Ok(String::new()), recognize(&Grid::filled(8, 6, false)));
+        let blob = Grid::filled(3, 6, true);
+        assert!(recognize(&blob)
+            .unwrap_err()
+            .starts_with("Unknown letter at column 0"));
+        let mut short = Grid::filled(3, 4, false);
+        short[(1, 1)] = true;
+        short[(1, 3)] = true;
+        assert_eq!(
+            Err("No font is 3 pixels tall".to_string()),
+            recognize(&short)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::Debug;
+
+use crate::interval::Interval;
+use crate::rng::Rng;
+
+/// Simpler variants of a value to try when minimizing a failing test input.
+pub trait Shrink: Sized {
+    /// Candidates, roughly simplest first; empty when nothing is simpler.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_integer {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            /// Steps toward zero: zero itself, half the value, then one less.
+            #[allow(unused_comparisons)]
+            fn shrink(&self) -> Vec<Self> {
+                let value = *self;
+                if value == 0 {
+                    return Vec::new();
+                }
+                let step = if value < 0 { value + 1 } else { value - 1 };
+                let mut candidates = vec![0, value / 2, step];
+                candidates.dedup();
+                candidates.retain(|x| *x != value);
+                candidates
+            }
+        }
+    )*};
+}
+
+shrink_integer!(i32, i64, u32, u64, usize);
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+/// Removes blocks of `len / 2`, `len / 4`, ... items down to single ones.
+fn without_blocks<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    let mut size = items.len();
+    while size > 0 {
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + size).min(items.len());
+            let mut candidate = items[..start].to_vec();
+            candidate.extend_from_slice(&items[end..]);
+            candidates.push(candidate);
+            start += size;
+        }
+        size /= 2;
+    }
+    candidates
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        without_blocks(&chars)
+            .into_iter()
+            .map(|x| x.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    /// Drops items first, then shrinks each remaining one in place.
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = without_blocks(self);
+        for (i, item) in self.iter().enumerate() {
+            for smaller in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates: Vec<Self> = self
+            .0
+            .shrink()
+            .into_iter()
+            .map(|x| (x, self.1.clone()))
+            .collect();
+        candidates.extend(self.1.shrink().into_iter().map(|x| (self.0.clone(), x)));
+        candidates
+    }
+}
+
+impl Shrink for Interval {
+    /// Moves either bound toward zero; candidates may be empty intervals.
+    fn shrink(&self) -> Vec<Self> {
+        (self.start, self.end)
+            .shrink()
+            .into_iter()
+            .map(|(start, end)| Interval::new(start, end))
+            .collect()
+    }
+}
+
+/// `Ok` when both values match, otherwise an error naming them.
+pub fn expect_eq<T: PartialEq + Debug>(expected: T, actual: T) -> Result<(), String> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, got {:?}", expected, actual))
+    }
+}
+
+/// Greedily replaces a failing value with the first simpler candidate that
+/// still fails, until none does, returning the minimal value, its failure
+/// and the number of steps taken.
+pub fn minimize<T, P>(value: T, message: String, property: P) -> (T, String, usize)
+where
+    T: Shrink,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let (mut value, mut message) = (value, message);
+    let mut steps = 0;
+    'shrinking: while steps < 10_000 {
+        for candidate in value.shrink() {
+            if let Err(failure) = property(&candidate) {
+                value = candidate;
+                message = failure;
+                steps += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (value, message, steps)
+}
+
+/// Checks `property` on `cases` generated inputs, panicking with a minimized
+/// counterexample on the first failure.
+///
+/// Properties should return `Ok` for inputs outside their preconditions, so
+/// shrinking never settles on an input the generator could not produce.
+pub fn check<T, G, P>(seed: u64, cases: usize, mut generate: G, property: P)
+where
+    T: Shrink + Debug,
+    G: FnMut(&mut Rng) -> T,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let mut rng = Rng::new(seed);
+    for case in 1..=cases {
+        let value = generate(&mut rng);
+        if let Err(message) = property(&value) {
+            let (value, message, steps) = minimize(value, message, &property);
+            panic!(
+                "Property failed on case {} of seed {} (shrunk {} times): {}\n{:#?}",
+                case, seed, steps, message, value
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prop_shrink_candidates() {
+        assert_eq!(vec![0, 5, 9], 10u32.shrink());
+        assert_eq!(vec![0, -2, -3], (-4i32).shrink());
+        assert_eq!(vec![0], 1usize.shrink());
+        assert!(0i64.shrink().is_empty());
+        assert_eq!(vec!["", "bc", "ac", "ab"], "abc".to_string().shrink());
+        let pairs = vec![(1u32, 2u32)].shrink();
+        assert_eq!(
+            vec![vec![], vec![(0, 2)], vec![(1, 0)], vec![(1, 1)]],
+            pairs
+        );
+    }
+
+    #[test]
+    fn prop_minimize() {
+        // Fails whenever the total exceeds 10, so a minimal input sums to 11.
+        let property = |xs: &Vec<u32>| {
+            if xs.iter().sum::<u32>() > 10 {
+                Err(format!("sum of {:?}", xs))
+            } else {
+                Ok(())
+            }
+        };
+        let start = vec![3, 9, 4, 20, 1];
+        let (value, message, steps) = minimize(start, String::new(), property);
+        assert_eq!(11, value.iter().sum::<u32>());
+        assert_eq!(format!("sum of {:?}", value), message);
+        assert!(steps > 0);
+        check(1, 100, |rng| vec![rng.below(3) as u32; 3], property);
+    }
+
+    #[test]
+    #[should_panic(expected = "(shrunk")]
+    fn prop_check_reports_failures() {
+        check(
+            1,
+            100,
+            |rng| rng.below(100) as u32,
+            |x| expect_eq(true, *x < 50),
+        );
+    }
+}
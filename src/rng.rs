@@ -0,0 +1,84 @@
+/// Small seeded pseudo-random generator (splitmix64).
+///
+/// Not suitable for anything security related, but fast, dependency free and
+/// reproducible across platforms, which is all the simulations need.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in `[0, n)`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            panic!("Cannot pick below zero");
+        }
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % n;
+            }
+        }
+    }
+
+    /// Picks an index with probability proportional to its weight.
+    pub fn weighted(&mut self, weights: &[f64]) -> usize {
+        let total: f64 = weights.iter().sum();
+        if weights.is_empty() || total <= 0.0 || weights.iter().any(|w| *w < 0.0) {
+            panic!("Unusable weights: {:?}", weights);
+        }
+        let mut target = self.next_f64() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return index;
+            }
+            target -= weight;
+        }
+        weights.iter().rposition(|w| *w > 0.0).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_reproducible() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let xs: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+        assert_eq!(xs, ys);
+        assert_ne!(xs[0], Rng::new(43).next_u64());
+    }
+
+    #[test]
+    fn rng_weighted_skips_zero_weights() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let index = rng.weighted(&[0.0, 1.0, 0.0, 3.0]);
+            assert!(index == 1 || index == 3);
+            assert!(rng.below(5) < 5);
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Nodes a search reached, each with the cheapest cost found to it and the
+/// node it was reached from, so paths can be rebuilt afterwards.
+///
+/// Costs are final for every node a search settled; when it stops early at a
+/// goal, nodes still waiting in its queue may have cheaper ways in.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    /// The goal the search stopped at, if it found one.
+    pub goal: Option<N>,
+    costs: HashMap<N, u64>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Search<N> {
+        Search {
+            goal: None,
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+
+    /// Records a way to `node` unless a cheaper or equal one is known,
+    /// returning whether it was recorded.
+    fn reach(&mut self, node: N, cost: u64, parent: Option<N>) -> bool {
+        if self.costs.get(&node).is_some_and(|x| *x <= cost) {
+            return false;
+        }
+        match parent {
+            Some(parent) => self.parents.insert(node.clone(), parent),
+            None => self.parents.remove(&node),
+        };
+        self.costs.insert(node, cost);
+        true
+    }
+
+    pub fn cost(&self, node: &N) -> Option<u64> {
+        self.costs.get(node).copied()
+    }
+
+    /// Nodes from the start the search came from up to `node`, both included.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        self.costs.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_cost(&self) -> Option<u64> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path(self.goal.as_ref()?)
+    }
+
+    /// Every node reached, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item = &N> {
+        self.costs.keys()
+    }
+}
+
+/// Breadth-first search where every edge costs 1, from all `starts` at once,
+/// stopping at the first node `is_goal` accepts or once everything reachable
+/// has been seen.
+///
+/// Searching backward, from the goals toward the starts, only takes a
+/// `neighbors` that returns the nodes with edges into a node instead.
+pub fn bfs<N, I, F, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: F,
+    mut is_goal: G,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.reach(start.clone(), 0, None) {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let cost = search.costs[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.costs.contains_key(&next) {
+                search.reach(next.clone(), cost, Some(node.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Cheapest paths when `neighbors` gives each edge a cost.
+pub fn dijkstra<N, I, F, G>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: F,
+    is_goal: G,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+    F: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar(starts, neighbors, |_| 0, is_goal)
+}
+
+/// Dijkstra's search ordered by cost so far plus `heuristic`, which must
+/// never overestimate the cost left to a goal for the goal's cost to be the
+/// cheapest.
+pub fn astar<N, I, F, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+    F: FnMut(&N) -> I,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    // Queued nodes live here so the heap only orders (estimate, index) pairs
+    // and nodes need no ordering of their own; ties go to the oldest entry.
+    let mut queued: Vec<(N, u64)> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if search.reach(start.clone(), 0, None) {
+            heap.push(Reverse((heuristic(&start), queued.len())));
+            queued.push((start, 0));
+        }
+    }
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (node, cost) = queued[index].clone();
+        if search.costs[&node] < cost {
+            continue;
+        }
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if search.reach(next.clone(), next_cost, Some(node.clone())) {
+                heap.push(Reverse((next_cost + heuristic(&next), queued.len())));
+                queued.push((next, next_cost));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_bfs() {
+        // Numbers on a line, each leading to the next one and its double.
+        let neighbors = |x: &u32| vec![x + 1, x * 2];
+        let search = bfs([1], neighbors, |x| *x == 10);
+        assert_eq!(Some(10), search.goal);
+        assert_eq!(Some(4), search.goal_cost());
+        assert_eq!(Some(vec![1, 2, 4, 5, 10]), search.goal_path());
+        assert_eq!(Some(vec![1, 2, 4]), search.path(&4));
+        assert_eq!(None, search.path(&1000));
+
+        let search = bfs([1, 9], neighbors, |x| *x == 10);
+        assert_eq!(Some(vec![9, 10]), search.goal_path());
+
+        // Nothing matches, so everything below the bound gets reached.
+        let search = bfs([0], |x: &u32| (*x < 5).then_some(x + 1), |_| false);
+        assert_eq!(None, search.goal);
+        assert_eq!(6, search.reached().count());
+        assert_eq!(Some(5), search.cost(&5));
+    }
+
+    #[test]
+    fn search_weighted() {
+        let edges: HashMap<char, Vec<(char, u64)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+        ]);
+        let neighbors = |x: &char| edges[x].clone();
+        let search = dijkstra(['a'], neighbors, |x| *x == 'e');
+        assert_eq!(Some(20), search.goal_cost());
+        assert_eq!(Some(vec!['a', 'c', 'f', 'e']), search.goal_path());
+        let everything = dijkstra(['a'], neighbors, |_| false);
+        assert_eq!(Some(20), everything.cost(&'d'));
+        assert_eq!(Some(11), everything.cost(&'f'));
+
+        // On an open grid the Manhattan distance is exact, so A* only
+        // expands nodes on some shortest path.
+        let goal = (6i64, 4i64);
+        let grid = |(x, y): &(i64, i64)| {
+            [(x + 1, *y), (x - 1, *y), (*x, y + 1), (*x, y - 1)]
+                .into_iter()
+                .filter(|(x, y)| (0..10).contains(x) && (0..10).contains(y))
+                .map(|x| (x, 1))
+                .collect::<Vec<_>>()
+        };
+        let distance = |(x, y): &(i64, i64)| x.abs_diff(goal.0) + y.abs_diff(goal.1);
+        let guided = astar([(0, 0)], grid, distance, |x| *x == goal);
+        let blind = dijkstra([(0, 0)], grid, |x| *x == goal);
+        assert_eq!(Some(10), guided.goal_cost());
+        assert_eq!(Some(10), blind.goal_cost());
+        assert_eq!(11, guided.goal_path().unwrap().len());
+        assert!(guided.reached().count() < blind.reached().count());
+    }
+}
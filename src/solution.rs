@@ -0,0 +1,149 @@
+use std::time::{Duration, Instant};
+
+/// One implementation of a puzzle part, turning the raw input into the answer.
+///
+/// A part can have several implementations under different names, such as a
+/// readable reference next to an optimized version, and they must all agree.
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub year: u32,
+    pub day: u32,
+    pub part: char,
+    pub name: &'static str,
+    pub run: fn(&str) -> Result<String, String>,
+}
+
+impl Solution {
+    pub fn new(
+        year: u32,
+        day: u32,
+        part: char,
+        name: &'static str,
+        run: fn(&str) -> Result<String, String>,
+    ) -> Solution {
+        Solution {
+            year,
+            day,
+            part,
+            name,
+            run,
+        }
+    }
+
+    /// Runs the solution, also returning how long it took.
+    pub fn timed(&self, input: &str) -> (Result<String, String>, Duration) {
+        let start = Instant::now();
+        let answer = (self.run)(input);
+        (answer, start.elapsed())
+    }
+}
+
+/// Every registered implementation, in year, day, part order with the
+/// reference implementation of each part first.
+pub fn solutions() -> Vec<Solution> {
+    crate::year2022::solutions()
+}
+
+/// Registered implementations of a year, optionally narrowed to a day and part.
+pub fn select(year: u32, day: Option<u32>, part: Option<char>) -> Vec<Solution> {
+    solutions()
+        .into_iter()
+        .filter(|x| x.year == year)
+        .filter(|x| day.is_none_or(|day| x.day == day))
+        .filter(|x| part.is_none_or(|part| x.part == part))
+        .collect()
+}
+
+/// Splits solutions into runs of the same year, day and part.
+pub fn by_part(solutions: &[Solution]) -> Vec<&[Solution]> {
+    solutions
+        .chunk_by(|a, b| (a.year, a.day, a.part) == (b.year, b.day, b.part))
+        .collect()
+}
+
+/// The answer all implementations agree on, or an error listing what each
+/// one returned.
+pub fn agree(answers: &[(&Solution, Result<String, String>)]) -> Result<String, String> {
+    let first = match answers.first() {
+        Some((_, answer)) => answer,
+        None => return Err("No implementations".to_string()),
+    };
+    if let (Ok(answer), true) = (first, answers.iter().all(|x| x.1 == *first)) {
+        return Ok(answer.clone());
+    }
+    let results: Vec<String> = answers
+        .iter()
+        .map(|(solution, answer)| match answer {
+            Ok(answer) => format!("{} gives {}", solution.name, answer),
+            Err(error) => format!("{} fails: {}", solution.name, error),
+        })
+        .collect();
+    Err(results.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(input: &str) -> Result<String, String> {
+        let value: i32 = input.trim().parse().map_err(|_| "Not a number")?;
+        Ok((2 * value).to_string())
+    }
+
+    fn add_self(input: &str) -> Result<String, String> {
+        let value: i32 = input.trim().parse().map_err(|_| "Not a number")?;
+        Ok((value + value).to_string())
+    }
+
+    fn square(input: &str) -> Result<String, String> {
+        let value: i32 = input.trim().parse().map_err(|_| "Not a number")?;
+        Ok((value * value).to_string())
+    }
+
+    #[test]
+    fn solution_agree() {
+        let doubled = Solution::new(2022, 1, 'a', "double", double);
+        let added = Solution::new(2022, 1, 'a', "add", add_self);
+        let squared = Solution::new(2022, 1, 'a', "square", square);
+        let run = |solutions: &[&Solution], input: &str| {
+            let answers: Vec<_> = solutions.iter().map(|x| (*x, (x.run)(input))).collect();
+            agree(&answers)
+        };
+        assert_eq!(Ok("6".to_string()), run(&[&doubled, &added], "3"));
+        assert_eq!(Ok("4".to_string()), run(&[&doubled, &squared], "2"));
+        assert_eq!(
+            Err("double gives 6, square gives 9".to_string()),
+            run(&[&doubled, &squared], "3")
+        );
+        assert_eq!(
+            Err("double fails: Not a number, add fails: Not a number".to_string()),
+            run(&[&doubled, &added], "x")
+        );
+    }
+
+    #[test]
+    fn solution_registry() {
+        let all = solutions();
+        let parts = by_part(&all);
+        assert!(parts.iter().all(|x| !x.is_empty()));
+        // Every part is registered in one run, so no part appears twice.
+        let mut keys: Vec<_> = parts
+            .iter()
+            .map(|x| (x[0].year, x[0].day, x[0].part))
+            .collect();
+        let count = keys.len();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(count, keys.len());
+        for part in parts {
+            let mut names: Vec<&str> = part.iter().map(|x| x.name).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(part.len(), names.len(), "{:?}", part[0]);
+        }
+        let day3 = select(2022, Some(3), Some('b'));
+        assert!(day3.len() > 1);
+        assert!(day3.iter().all(|x| x.day == 3 && x.part == 'b'));
+        assert!(select(1999, None, None).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+/// The last `size` bytes of a stream with a running count of how many of them
+/// are distinct, so each pushed byte costs O(1) whatever the window size.
+#[derive(Debug, Clone)]
+pub struct DistinctWindow {
+    size: usize,
+    bytes: VecDeque<u8>,
+    counts: [u32; 256],
+    distinct: usize,
+}
+
+impl DistinctWindow {
+    pub fn new(size: usize) -> DistinctWindow {
+        DistinctWindow {
+            size,
+            bytes: VecDeque::with_capacity(size + 1),
+            counts: [0; 256],
+            distinct: 0,
+        }
+    }
+
+    /// Adds a byte, dropping the oldest one once the window is full, and
+    /// returns whether the window now holds `size` distinct bytes.
+    pub fn push(&mut self, byte: u8) -> bool {
+        self.bytes.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        if self.bytes.len() > self.size {
+            let oldest = self.bytes.pop_front().unwrap();
+            self.counts[oldest as usize] -= 1;
+            if self.counts[oldest as usize] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        self.all_distinct()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of bytes held, which is `size` once enough have been pushed.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Number of different bytes in the window.
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    /// Whether the window is full and no byte in it repeats.
+    pub fn all_distinct(&self) -> bool {
+        self.bytes.len() == self.size && self.distinct == self.size
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.counts = [0; 256];
+        self.distinct = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_distinct_counts() {
+        let mut window = DistinctWindow::new(3);
+        assert!(window.is_empty());
+        assert!(!window.push(b'a'));
+        assert!(!window.push(b'b'));
+        assert_eq!((2, 2), (window.len(), window.distinct()));
+        assert!(!window.push(b'a'));
+        assert_eq!((3, 2), (window.len(), window.distinct()));
+        assert!(window.push(b'c'));
+        assert!(!window.push(b'c'));
+        assert!(!window.push(b'd'));
+        assert!(window.push(b'e'));
+        assert_eq!(3, window.len());
+        window.clear();
+        assert_eq!((0, 0), (window.len(), window.distinct()));
+
+        let mut empty = DistinctWindow::new(0);
+        assert!(empty.push(b'x'));
+        assert!(empty.is_empty());
+    }
+}
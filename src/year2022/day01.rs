@@ -0,0 +1,192 @@
+//! Calorie counting: the elves carrying the most snacks.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::solution::Solution;
+
+pub fn parse_input(text: &str) -> Vec<Vec<i32>> {
+    let mut groups = Vec::new();
+    for line in text.trim().lines() {
+        if groups.is_empty() {
+            groups.push(Vec::new());
+        }
+        if line.trim().is_empty() {
+            groups.push(Vec::new());
+            continue;
+        }
+        let value = line.parse().unwrap();
+        let group = groups.last_mut().unwrap();
+        group.push(value);
+    }
+    groups
+}
+
+pub fn most_calories(groups: &[Vec<i32>]) -> i32 {
+    groups.iter().map(|x| x.iter().sum()).max().unwrap()
+}
+
+/// Sum of the `top` largest group totals, sorting every total.
+pub fn top_calories(groups: &[Vec<i32>], top: usize) -> i32 {
+    let mut sums: Vec<i32> = groups.iter().map(|x| x.iter().sum()).collect();
+    sums.sort();
+    sums.iter().rev().take(top).sum::<i32>()
+}
+
+/// Same as `top_calories`, keeping only the `top` largest totals in a min-heap.
+pub fn top_calories_heap(groups: &[Vec<i32>], top: usize) -> i32 {
+    let mut heap = BinaryHeap::with_capacity(top + 1);
+    for group in groups {
+        heap.push(Reverse(group.iter().sum::<i32>()));
+        if heap.len() > top {
+            heap.pop();
+        }
+    }
+    heap.into_iter().map(|Reverse(x)| x).sum()
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 1, 'a', "max", |text| {
+            Ok(most_calories(&parse_input(text)).to_string())
+        }),
+        Solution::new(2022, 1, 'a', "heap", |text| {
+            Ok(top_calories_heap(&parse_input(text), 1).to_string())
+        }),
+        Solution::new(2022, 1, 'b', "sort", |text| {
+            Ok(top_calories(&parse_input(text), 3).to_string())
+        }),
+        Solution::new(2022, 1, 'b', "heap", |text| {
+            Ok(top_calories_heap(&parse_input(text), 3).to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, prop};
+
+    const EXAMPLE: &str = "
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn day01a_example1() {
+        let groups = parse_input(EXAMPLE);
+        assert_eq!(24000, most_calories(&groups));
+    }
+
+    #[test]
+    fn day01b_example1() {
+        let groups = parse_input(EXAMPLE);
+        assert_eq!(45000, top_calories(&groups, 3));
+    }
+
+    #[test]
+    fn day01_solutions_example1() {
+        for solution in solutions() {
+            let expected = if solution.part == 'a' {
+                "24000"
+            } else {
+                "45000"
+            };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    fn naive_most_calories(groups: &[Vec<i32>]) -> i32 {
+        let mut best = 0;
+        for group in groups {
+            let mut total = 0;
+            for calories in group {
+                total += calories;
+            }
+            if total > best {
+                best = total;
+            }
+        }
+        best
+    }
+
+    fn naive_top_three(groups: &[Vec<i32>]) -> i32 {
+        let mut totals = Vec::new();
+        for group in groups {
+            let mut total = 0;
+            for calories in group {
+                total += calories;
+            }
+            totals.push(total);
+        }
+        let mut sum = 0;
+        for _ in 0..3 {
+            if totals.is_empty() {
+                break;
+            }
+            let mut best = 0;
+            for (i, total) in totals.iter().enumerate() {
+                if *total > totals[best] {
+                    best = i;
+                }
+            }
+            sum += totals.remove(best);
+        }
+        sum
+    }
+
+    #[test]
+    fn day01a_matches_naive() {
+        prop::check(
+            1,
+            2000,
+            |rng| {
+                let count = 1 + rng.below(30) as usize;
+                generate::calorie_groups(rng, count)
+            },
+            |groups| {
+                if groups.is_empty() || groups.iter().any(|x| x.is_empty()) {
+                    return Ok(());
+                }
+                let text = generate::calories_input(groups);
+                prop::expect_eq(
+                    naive_most_calories(groups),
+                    most_calories(&parse_input(&text)),
+                )
+            },
+        );
+    }
+
+    #[test]
+    fn day01b_matches_naive() {
+        prop::check(
+            1,
+            2000,
+            |rng| {
+                let count = 1 + rng.below(30) as usize;
+                generate::calorie_groups(rng, count)
+            },
+            |groups| {
+                if groups.is_empty() || groups.iter().any(|x| x.is_empty()) {
+                    return Ok(());
+                }
+                let groups = parse_input(&generate::calories_input(groups));
+                let expected = naive_top_three(&groups);
+                prop::expect_eq(expected, top_calories(&groups, 3))?;
+                prop::expect_eq(expected, top_calories_heap(&groups, 3))
+            },
+        );
+    }
+}
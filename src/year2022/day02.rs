@@ -0,0 +1,601 @@
+//! Rock paper scissors: scoring the strategy guide.
+
+use std::str::FromStr;
+
+use crate::rng::Rng;
+use crate::solution::Solution;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RpsMove {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl FromStr for RpsMove {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" | "X" => Ok(RpsMove::Rock),
+            "B" | "Y" => Ok(RpsMove::Paper),
+            "C" | "Z" => Ok(RpsMove::Scissors),
+            unknown => Err(format!("Not an RPS move: {:?}", unknown)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RpsOutcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl FromStr for RpsOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" => Ok(RpsOutcome::Lose),
+            "Y" => Ok(RpsOutcome::Draw),
+            "Z" => Ok(RpsOutcome::Win),
+            unknown => Err(format!("Not an RPS strategy: {:?}", unknown)),
+        }
+    }
+}
+
+pub const MOVES: [RpsMove; 3] = [RpsMove::Rock, RpsMove::Paper, RpsMove::Scissors];
+pub const OUTCOMES: [RpsOutcome; 3] = [RpsOutcome::Lose, RpsOutcome::Draw, RpsOutcome::Win];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RpsRoundStrategy {
+    pub opponent: RpsMove,
+    pub outcome: RpsOutcome,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RpsRound {
+    pub opponent: RpsMove,
+    pub player: RpsMove,
+}
+
+impl FromStr for RpsRound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.splitn(2, " ").collect();
+        Ok(RpsRound {
+            opponent: parts.first().unwrap().parse()?,
+            player: parts.get(1).ok_or("Missing player move")?.parse()?,
+        })
+    }
+}
+
+impl RpsRound {
+    pub fn from_strategy(strat: RpsRoundStrategy) -> RpsRound {
+        let move_count: i32 = MOVES.len().try_into().unwrap();
+        let opponent_idx: i32 = MOVES
+            .iter()
+            .position(|x| *x == strat.opponent)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let player_idx = match strat.outcome {
+            RpsOutcome::Lose => opponent_idx - 1,
+            RpsOutcome::Draw => opponent_idx,
+            RpsOutcome::Win => opponent_idx + 1,
+        };
+        let player_idx: usize = ((player_idx + move_count) % move_count).try_into().unwrap();
+        let player = MOVES.get(player_idx).unwrap();
+        RpsRound {
+            opponent: strat.opponent,
+            player: *player,
+        }
+    }
+
+    pub fn is_tie(&self) -> bool {
+        self.opponent == self.player
+    }
+
+    pub fn is_win(&self) -> bool {
+        (self.player == RpsMove::Rock && self.opponent == RpsMove::Scissors)
+            || (self.player == RpsMove::Scissors && self.opponent == RpsMove::Paper)
+            || (self.player == RpsMove::Paper && self.opponent == RpsMove::Rock)
+    }
+
+    pub fn get_score(&self) -> i32 {
+        let mut points = 0;
+        points += match self.player {
+            RpsMove::Rock => 1,
+            RpsMove::Paper => 2,
+            RpsMove::Scissors => 3,
+        };
+        if self.is_win() {
+            points += 6;
+        } else if self.is_tie() {
+            points += 3;
+        } else {
+            points += 0;
+        }
+        points
+    }
+}
+
+/// Part a rounds, skipping lines that are not two moves.
+pub fn parse_input(text: &str) -> Vec<RpsRound> {
+    text.trim().lines().filter_map(|x| x.parse().ok()).collect()
+}
+
+/// Part b rounds, reading the second letter as the outcome to aim for.
+pub fn parse_strategies(text: &str) -> Vec<RpsRoundStrategy> {
+    let mut rounds = Vec::new();
+    for line in text.trim().lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let parts: Vec<_> = line.splitn(2, " ").collect();
+        if parts.len() != 2 || !["A", "B", "C"].contains(&parts[0]) {
+            panic!("Unknown input line: {:?}", line);
+        }
+        let opponent = parts[0].parse().unwrap();
+        let outcome = parts[1].parse().unwrap();
+        rounds.push(RpsRoundStrategy { opponent, outcome });
+    }
+    rounds
+}
+
+pub fn total_score(rounds: &[RpsRound]) -> i32 {
+    rounds.iter().map(|x| x.get_score()).sum()
+}
+
+pub fn total_strategy_score(strats: &[RpsRoundStrategy]) -> i32 {
+    strats
+        .iter()
+        .map(|x| RpsRound::from_strategy(*x))
+        .map(|x| x.get_score())
+        .sum()
+}
+
+/// Scores indexed by opponent letter (`A`-`C`), then second letter (`X`-`Z`).
+const MOVE_SCORES: [[i32; 3]; 3] = [[4, 8, 3], [1, 5, 9], [7, 2, 6]];
+const OUTCOME_SCORES: [[i32; 3]; 3] = [[3, 4, 8], [1, 5, 9], [2, 6, 7]];
+
+/// Totals a guide by looking each letter pair up in `table`, without building
+/// any rounds.
+pub fn table_score(text: &str, table: &[[i32; 3]; 3]) -> Result<i32, String> {
+    let mut total = 0;
+    for line in text.trim().lines() {
+        match line.as_bytes() {
+            [lhs @ b'A'..=b'C', b' ', rhs @ b'X'..=b'Z'] => {
+                total += table[(lhs - b'A') as usize][(rhs - b'X') as usize];
+            }
+            _ => return Err(format!("Unknown input line: {:?}", line)),
+        }
+    }
+    Ok(total)
+}
+
+/// Rounds as (opponent move, index of the X/Y/Z letter), leaving open what
+/// the second letter means.
+pub fn parse_guide(text: &str) -> Result<Vec<(RpsMove, usize)>, String> {
+    let mut guide = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = || format!("line {}: not a guide entry: {:?}", i + 1, line);
+        let (lhs, rhs) = line.split_once(' ').ok_or_else(error)?;
+        if !["A", "B", "C"].contains(&lhs) {
+            return Err(error());
+        }
+        let letter = ["X", "Y", "Z"]
+            .iter()
+            .position(|x| *x == rhs)
+            .ok_or_else(error)?;
+        guide.push((lhs.parse()?, letter));
+    }
+    Ok(guide)
+}
+
+/// Every way of giving the X/Y/Z letters three different meanings.
+pub fn all_mappings<T: Copy + PartialEq>(meanings: [T; 3]) -> Vec<[T; 3]> {
+    let mut mappings = Vec::new();
+    for x in meanings {
+        for y in meanings {
+            for z in meanings {
+                if x != y && y != z && x != z {
+                    mappings.push([x, y, z]);
+                }
+            }
+        }
+    }
+    mappings
+}
+
+/// Total of the guide when each letter means `mapping[letter]`, with `score`
+/// giving the points for one round.
+pub fn score_with_mapping<T: Copy, F: Fn(RpsMove, T) -> i32>(
+    guide: &[(RpsMove, usize)],
+    mapping: &[T; 3],
+    score: F,
+) -> i32 {
+    guide
+        .iter()
+        .map(|(opponent, letter)| score(*opponent, mapping[*letter]))
+        .sum()
+}
+
+/// Exhaustively scores every mapping of the letters onto `meanings`,
+/// returning the best and worst (mapping, total) pairs.
+pub fn optimize_mapping<T: Copy + PartialEq, F: Fn(RpsMove, T) -> i32>(
+    guide: &[(RpsMove, usize)],
+    meanings: [T; 3],
+    score: F,
+) -> (([T; 3], i32), ([T; 3], i32)) {
+    let scored: Vec<([T; 3], i32)> = all_mappings(meanings)
+        .into_iter()
+        .map(|x| (x, score_with_mapping(guide, &x, &score)))
+        .collect();
+    let best = *scored.iter().max_by_key(|x| x.1).unwrap();
+    let worst = *scored.iter().min_by_key(|x| x.1).unwrap();
+    (best, worst)
+}
+
+/// Opponent move weights (rock, paper, scissors) the simulation is run with
+/// unless others are given.
+pub const OPPONENTS: [(&str, [f64; 3]); 4] = [
+    ("uniform", [1.0, 1.0, 1.0]),
+    ("rock-heavy", [2.0, 1.0, 1.0]),
+    ("paper-heavy", [1.0, 2.0, 1.0]),
+    ("scissors-heavy", [1.0, 1.0, 2.0]),
+];
+
+/// Opponents, trial count and seed for the bins' simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationConfig {
+    /// Named opponent move weights (rock, paper, scissors).
+    pub opponents: Vec<(String, [f64; 3])>,
+    pub trials: usize,
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            opponents: OPPONENTS
+                .iter()
+                .map(|(name, weights)| (name.to_string(), *weights))
+                .collect(),
+            trials: 1000,
+            seed: 2022,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Reads `--opponent=ROCK,PAPER,SCISSORS` (once per opponent),
+    /// `--trials=N` and `--seed=N`, keeping the defaults for anything not
+    /// given. Opponents given replace all of `OPPONENTS`.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<SimulationConfig, String> {
+        let mut config = SimulationConfig::default();
+        let mut opponents = Vec::new();
+        for arg in args {
+            if let Some(weights) = arg.strip_prefix("--opponent=") {
+                opponents.push((weights.to_string(), parse_weights(weights)?));
+            } else if let Some(trials) = arg.strip_prefix("--trials=") {
+                config.trials = match trials.parse() {
+                    Ok(trials) if trials > 0 => trials,
+                    _ => return Err(format!("Not a positive trial count: {:?}", trials)),
+                };
+            } else if let Some(seed) = arg.strip_prefix("--seed=") {
+                config.seed = seed
+                    .parse()
+                    .map_err(|_| format!("Not a seed: {:?}", seed))?;
+            } else {
+                return Err(format!("Unknown argument: {:?}", arg));
+            }
+        }
+        if !opponents.is_empty() {
+            config.opponents = opponents;
+        }
+        Ok(config)
+    }
+}
+
+/// Parses `rock,paper,scissors` weights: three non-negative numbers, not all
+/// zero.
+pub fn parse_weights(text: &str) -> Result<[f64; 3], String> {
+    let error = || format!("Not rock,paper,scissors weights: {:?}", text);
+    let weights: Vec<f64> = text
+        .split(',')
+        .map(|x| x.trim().parse().map_err(|_| error()))
+        .collect::<Result<_, _>>()?;
+    let usable =
+        weights.iter().all(|x| x.is_finite() && *x >= 0.0) && weights.iter().sum::<f64>() > 0.0;
+    match weights[..] {
+        [rock, paper, scissors] if usable => Ok([rock, paper, scissors]),
+        _ => Err(error()),
+    }
+}
+
+/// One line per opponent in `config` with the mean and variance of the
+/// total score `players` get against it.
+pub fn simulation_report(players: &[RpsMove], config: &SimulationConfig) -> String {
+    let mut text = String::new();
+    for (name, weights) in &config.opponents {
+        if let Some((mean, variance)) = simulate(players, *weights, config.trials, config.seed) {
+            text.push_str(&format!(
+                "Against {name} opponent: mean {mean:.1}, variance {variance:.1}\n"
+            ));
+        }
+    }
+    text
+}
+
+/// Plays `players` against an opponent drawing each move from `opponent`
+/// weights (rock, paper, scissors), returning the mean and sample variance of
+/// the total score over `trials` games, or `None` without any trials.
+pub fn simulate(
+    players: &[RpsMove],
+    opponent: [f64; 3],
+    trials: usize,
+    seed: u64,
+) -> Option<(f64, f64)> {
+    if trials == 0 {
+        return None;
+    }
+    let mut rng = Rng::new(seed);
+    let mut totals = Vec::with_capacity(trials);
+    for _ in 0..trials {
+        let total: i32 = players
+            .iter()
+            .map(|x| RpsRound {
+                opponent: MOVES[rng.weighted(&opponent)],
+                player: *x,
+            })
+            .map(|x| x.get_score())
+            .sum();
+        totals.push(total as f64);
+    }
+    let mean = totals.iter().sum::<f64>() / trials as f64;
+    let variance = if trials > 1 {
+        totals.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (trials - 1) as f64
+    } else {
+        0.0
+    };
+    Some((mean, variance))
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 2, 'a', "rounds", |text| {
+            Ok(total_score(&parse_input(text)).to_string())
+        }),
+        Solution::new(2022, 2, 'a', "table", |text| {
+            table_score(text, &MOVE_SCORES).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 2, 'b', "rounds", |text| {
+            Ok(total_strategy_score(&parse_strategies(text)).to_string())
+        }),
+        Solution::new(2022, 2, 'b', "table", |text| {
+            table_score(text, &OUTCOME_SCORES).map(|x| x.to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, prop};
+
+    const EXAMPLE: &str = "
+A Y
+B X
+C Z
+";
+
+    #[test]
+    fn day02a_test_parse_bad() {
+        let input = "E";
+        let mov = input.parse::<RpsMove>();
+        assert!(mov.is_err());
+        let rounds = parse_input(input);
+        assert_eq!(0, rounds.len());
+    }
+
+    #[test]
+    fn day02a_test_parse_good() {
+        let input = "A Y";
+        let rounds = parse_input(input);
+        assert_eq!(1, rounds.len());
+        let round = rounds.last().unwrap();
+        assert_eq!(
+            RpsRound {
+                opponent: RpsMove::Rock,
+                player: RpsMove::Paper,
+            },
+            *round
+        );
+    }
+
+    #[test]
+    fn day02a_example1() {
+        let rounds = parse_input(EXAMPLE);
+        let score = total_score(&rounds);
+        assert_eq!(15, score);
+    }
+
+    #[test]
+    fn day02b_example1() {
+        let rounds = parse_strategies(EXAMPLE);
+        let score = total_strategy_score(&rounds);
+        assert_eq!(12, score);
+    }
+
+    #[test]
+    fn day02_solutions_example1() {
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "15" } else { "12" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+        assert!(table_score("A Y\nD X", &MOVE_SCORES).is_err());
+    }
+
+    #[test]
+    fn day02a_example1_optimize() {
+        let guide = parse_guide(EXAMPLE).unwrap();
+        let score = |opponent, player| RpsRound { opponent, player }.get_score();
+        assert_eq!(6, all_mappings(MOVES).len());
+        assert_eq!(15, score_with_mapping(&guide, &MOVES, score));
+        let (best, worst) = optimize_mapping(&guide, MOVES, score);
+        assert_eq!(24, best.1);
+        assert_eq!([RpsMove::Scissors, RpsMove::Paper, RpsMove::Rock], best.0);
+        assert_eq!(6, worst.1);
+        assert_eq!([RpsMove::Rock, RpsMove::Scissors, RpsMove::Paper], worst.0);
+        assert_eq!(
+            Err("line 2: not a guide entry: \"B W\"".to_string()),
+            parse_guide("A Y\nB W")
+        );
+        assert!(parse_guide("D X").is_err());
+    }
+
+    #[test]
+    fn day02b_example1_optimize() {
+        let guide = parse_guide(EXAMPLE).unwrap();
+        let score = |opponent, outcome| {
+            RpsRound::from_strategy(RpsRoundStrategy { opponent, outcome }).get_score()
+        };
+        assert_eq!(12, score_with_mapping(&guide, &OUTCOMES, score));
+        let (best, worst) = optimize_mapping(&guide, OUTCOMES, score);
+        assert_eq!(18, best.1);
+        assert_eq!(
+            [RpsOutcome::Win, RpsOutcome::Lose, RpsOutcome::Draw],
+            best.0
+        );
+        assert_eq!(12, worst.1);
+        assert_eq!(OUTCOMES, worst.0);
+    }
+
+    #[test]
+    fn day02_simulate() {
+        let players = [RpsMove::Rock, RpsMove::Paper, RpsMove::Scissors];
+        let (mean, variance) = simulate(&players, [1.0, 0.0, 0.0], 10, 1).unwrap();
+        assert_eq!(4.0 + 8.0 + 3.0, mean);
+        assert_eq!(0.0, variance);
+        let (mean, variance) = simulate(&players, [1.0, 1.0, 1.0], 5000, 1).unwrap();
+        assert!((mean - 15.0).abs() < 0.5);
+        assert!(variance > 0.0);
+        assert_eq!(
+            Some((mean, variance)),
+            simulate(&players, [1.0, 1.0, 1.0], 5000, 1)
+        );
+        assert_eq!(None, simulate(&players, [1.0, 1.0, 1.0], 0, 1));
+    }
+
+    #[test]
+    fn day02_simulation_config() {
+        let args = |x: &[&str]| SimulationConfig::from_args(x.iter().map(|x| x.to_string()));
+        assert_eq!(Ok(SimulationConfig::default()), args(&[]));
+        let config = args(&["--opponent=1,0,0", "--trials=3", "--seed=7"]).unwrap();
+        assert_eq!(
+            vec![("1,0,0".to_string(), [1.0, 0.0, 0.0])],
+            config.opponents
+        );
+        assert_eq!((3, 7), (config.trials, config.seed));
+        let players = [RpsMove::Rock, RpsMove::Paper];
+        assert_eq!(
+            "Against 1,0,0 opponent: mean 12.0, variance 0.0\n",
+            simulation_report(&players, &config)
+        );
+        assert_eq!(4, args(&["--seed=1"]).unwrap().opponents.len());
+        assert_eq!(Ok([2.0, 1.0, 0.5]), parse_weights("2, 1,0.5"));
+        for bad in ["1,1", "1,1,1,1", "0,0,0", "1,-1,1", "1,x,1", "inf,1,1"] {
+            assert_eq!(
+                Err(format!("Not rock,paper,scissors weights: {:?}", bad)),
+                parse_weights(bad)
+            );
+        }
+        assert!(args(&["--trials=0"]).is_err());
+        assert!(args(&["--seed=-1"]).is_err());
+        assert_eq!(
+            Err("Unknown argument: \"--fast\"".to_string()),
+            args(&["--fast"])
+        );
+    }
+
+    fn naive_score_a(rounds: &[(char, char)]) -> i32 {
+        let mut total = 0;
+        for round in rounds {
+            total += match round {
+                ('A', 'X') => 1 + 3,
+                ('A', 'Y') => 2 + 6,
+                ('A', 'Z') => 3,
+                ('B', 'X') => 1,
+                ('B', 'Y') => 2 + 3,
+                ('B', 'Z') => 3 + 6,
+                ('C', 'X') => 1 + 6,
+                ('C', 'Y') => 2,
+                ('C', 'Z') => 3 + 3,
+                _ => panic!("Unknown round {:?}", round),
+            };
+        }
+        total
+    }
+
+    fn naive_score_b(rounds: &[(char, char)]) -> i32 {
+        let mut total = 0;
+        for round in rounds {
+            total += match round {
+                ('A', 'X') => 3,
+                ('A', 'Y') => 1 + 3,
+                ('A', 'Z') => 2 + 6,
+                ('B', 'X') => 1,
+                ('B', 'Y') => 2 + 3,
+                ('B', 'Z') => 3 + 6,
+                ('C', 'X') => 2,
+                ('C', 'Y') => 3 + 3,
+                ('C', 'Z') => 1 + 6,
+                _ => panic!("Unknown round {:?}", round),
+            };
+        }
+        total
+    }
+
+    #[test]
+    fn day02a_matches_naive() {
+        prop::check(
+            2,
+            2000,
+            |rng| {
+                let count = rng.below(50) as usize;
+                generate::strategy_guide(rng, count)
+            },
+            |rounds| {
+                let text = generate::strategy_input(rounds);
+                let expected = naive_score_a(rounds);
+                prop::expect_eq(expected, total_score(&parse_input(&text)))?;
+                prop::expect_eq(Ok(expected), table_score(&text, &MOVE_SCORES))
+            },
+        );
+    }
+
+    #[test]
+    fn day02b_matches_naive() {
+        prop::check(
+            2,
+            2000,
+            |rng| {
+                let count = rng.below(50) as usize;
+                generate::strategy_guide(rng, count)
+            },
+            |rounds| {
+                let text = generate::strategy_input(rounds);
+                let expected = naive_score_b(rounds);
+                prop::expect_eq(expected, total_strategy_score(&parse_strategies(&text)))?;
+                prop::expect_eq(Ok(expected), table_score(&text, &OUTCOME_SCORES))
+            },
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Rucksack reorganization: items shared between compartments and groups.
+
+use crate::itemset::ItemSet;
+use crate::solution::Solution;
+
+/// The non-blank lines of the input, rejecting any that cannot be split into
+/// two compartments of rucksack items.
+pub fn parse_input(text: &str) -> Result<Vec<String>, String> {
+    let mut containers = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        find_shared(line).map_err(|e| format!("Line {}: {}", index + 1, e))?;
+        containers.push(line.to_string());
+    }
+    Ok(containers)
+}
+
+pub fn compartments(container: &str) -> (&str, &str) {
+    container.split_at(container.len() / 2)
+}
+
+pub fn find_shared(container: &str) -> Result<ItemSet, String> {
+    if !container.len().is_multiple_of(2) {
+        return Err(format!(
+            "Unbalanced rucksack (len: {}): {:?}",
+            container.len(),
+            container
+        ));
+    }
+    let (lhs, rhs) = compartments(container);
+    let lhs: ItemSet = lhs.parse()?;
+    let rhs: ItemSet = rhs.parse()?;
+    Ok(lhs & rhs)
+}
+
+pub fn find_shared_item(container: &str) -> Result<char, String> {
+    let shared = find_shared(container)?;
+    match shared.len() {
+        1 => Ok(shared.iter().next().unwrap()),
+        0 => Err(format!("No shared items in {:?}", container)),
+        _ => Err(format!(
+            "Multiple shared items {:?} in {:?}",
+            shared.iter().collect::<Vec<_>>(),
+            container
+        )),
+    }
+}
+
+pub fn validate(containers: &[String]) -> Vec<String> {
+    containers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, x)| {
+            find_shared_item(x)
+                .err()
+                .map(|e| format!("Rucksack {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+pub fn get_priority(item: char) -> i32 {
+    match ItemSet::priority(item) {
+        Some(priority) => priority as i32,
+        None => panic!("Unknown priority for item: {:?}", item),
+    }
+}
+
+pub fn total_priority(containers: &[String]) -> Result<i32, Vec<String>> {
+    let errors = validate(containers);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let items = containers
+        .iter()
+        .map(|x| find_shared_item(x))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| vec![e])?;
+    Ok(items.into_iter().map(get_priority).sum())
+}
+
+pub fn group_rucksacks(containers: &[String], size: usize) -> Result<Vec<Vec<String>>, String> {
+    if size == 0 || !containers.len().is_multiple_of(size) {
+        return Err(format!(
+            "Cannot split {} rucksacks into groups of {}",
+            containers.len(),
+            size
+        ));
+    }
+    Ok(containers.chunks(size).map(|x| x.to_vec()).collect())
+}
+
+pub fn find_badge(group: &[String]) -> Result<char, String> {
+    let shared = group
+        .iter()
+        .map(|x| x.parse::<ItemSet>())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .fold(ItemSet::all(), ItemSet::intersection);
+    if shared.len() != 1 {
+        return Err(format!(
+            "Unexpected badge result {:?} for group {:?}",
+            shared.iter().collect::<Vec<_>>(),
+            group
+        ));
+    }
+    Ok(shared.iter().next().unwrap())
+}
+
+pub fn total_badge_priority(groups: &[Vec<String>]) -> Result<i32, String> {
+    let mut total = 0;
+    for group in groups {
+        total += get_priority(find_badge(group)?);
+    }
+    Ok(total)
+}
+
+/// The first item of the left compartment found in the right one, comparing
+/// every pair of items.
+pub fn loops_shared_item(container: &str) -> Result<char, String> {
+    let (lhs, rhs) = compartments(container);
+    for a in lhs.chars() {
+        for b in rhs.chars() {
+            if a == b {
+                return Ok(a);
+            }
+        }
+    }
+    Err(format!("No shared items in {:?}", container))
+}
+
+/// The first item of the first rucksack that every other rucksack contains,
+/// scanning each one item by item.
+pub fn loops_badge(group: &[String]) -> Result<char, String> {
+    let first = group.first().ok_or("Empty group")?;
+    for a in first.chars() {
+        let mut everywhere = true;
+        for other in &group[1..] {
+            let mut found = false;
+            for b in other.chars() {
+                if a == b {
+                    found = true;
+                    break;
+                }
+            }
+            everywhere &= found;
+        }
+        if everywhere {
+            return Ok(a);
+        }
+    }
+    Err(format!("No badge for group {:?}", group))
+}
+
+/// Partitions shuffled rucksacks into groups of `size` that each share
+/// exactly one badge, returning the rucksack indices of every group.
+///
+/// Each step anchors a group on the first unassigned rucksack and backtracks
+/// over the remaining ones, so an error means no such partition exists.
+/// Candidates are tried in input order, so when consecutive triples already
+/// form valid groups that is the partition returned.
+pub fn discover_groups(containers: &[String], size: usize) -> Result<Vec<Vec<usize>>, String> {
+    if size == 0 || !containers.len().is_multiple_of(size) {
+        return Err(format!(
+            "Cannot split {} rucksacks into groups of {}",
+            containers.len(),
+            size
+        ));
+    }
+    let sets = containers
+        .iter()
+        .map(|x| x.parse::<ItemSet>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut used = vec![false; sets.len()];
+    let mut groups = Vec::new();
+    if extend_partition(&sets, size, &mut used, &mut groups) {
+        Ok(groups)
+    } else {
+        Err(format!(
+            "No partition of {} rucksacks into groups of {} with exactly one badge each",
+            containers.len(),
+            size
+        ))
+    }
+}
+
+fn extend_partition(
+    sets: &[ItemSet],
+    size: usize,
+    used: &mut [bool],
+    groups: &mut Vec<Vec<usize>>,
+) -> bool {
+    let anchor = match used.iter().position(|x| !x) {
+        Some(anchor) => anchor,
+        None => return true,
+    };
+    used[anchor] = true;
+    let mut group = vec![anchor];
+    if fill_group(sets, size, used, groups, &mut group, sets[anchor]) {
+        return true;
+    }
+    used[anchor] = false;
+    false
+}
+
+fn fill_group(
+    sets: &[ItemSet],
+    size: usize,
+    used: &mut [bool],
+    groups: &mut Vec<Vec<usize>>,
+    group: &mut Vec<usize>,
+    shared: ItemSet,
+) -> bool {
+    if group.len() == size {
+        if shared.len() != 1 {
+            return false;
+        }
+        groups.push(group.clone());
+        if extend_partition(sets, size, used, groups) {
+            return true;
+        }
+        groups.pop();
+        return false;
+    }
+    let start = group.last().unwrap() + 1;
+    for candidate in start..sets.len() {
+        if used[candidate] {
+            continue;
+        }
+        let next = shared & sets[candidate];
+        if next.is_empty() {
+            continue;
+        }
+        used[candidate] = true;
+        group.push(candidate);
+        if fill_group(sets, size, used, groups, group, next) {
+            return true;
+        }
+        group.pop();
+        used[candidate] = false;
+    }
+    false
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 3, 'a', "itemset", |text| {
+            total_priority(&parse_input(text)?)
+                .map(|x| x.to_string())
+                .map_err(|x| x.join("; "))
+        }),
+        Solution::new(2022, 3, 'a', "loops", |text| {
+            let mut total = 0;
+            for container in parse_input(text)? {
+                total += get_priority(loops_shared_item(&container)?);
+            }
+            Ok(total.to_string())
+        }),
+        Solution::new(2022, 3, 'b', "itemset", |text| {
+            let groups = group_rucksacks(&parse_input(text)?, 3)?;
+            total_badge_priority(&groups).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 3, 'b', "discover", |text| {
+            let containers = parse_input(text)?;
+            let mut total = 0;
+            for group in discover_groups(&containers, 3)? {
+                let group: Vec<String> = group.iter().map(|i| containers[*i].clone()).collect();
+                total += get_priority(find_badge(&group)?);
+            }
+            Ok(total.to_string())
+        }),
+        Solution::new(2022, 3, 'b', "loops", |text| {
+            let mut total = 0;
+            for group in group_rucksacks(&parse_input(text)?, 3)? {
+                total += get_priority(loops_badge(&group)?);
+            }
+            Ok(total.to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, prop};
+
+    const EXAMPLE: &str = "
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn day03a_priority() {
+        assert_eq!(1, get_priority('a'));
+        assert_eq!(26, get_priority('z'));
+        assert_eq!(27, get_priority('A'));
+        assert_eq!(52, get_priority('Z'));
+    }
+
+    #[test]
+    fn day03a_example1() -> Result<(), String> {
+        let containers = parse_input(EXAMPLE)?;
+        let shared = containers
+            .iter()
+            .map(|x| find_shared_item(x))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(vec!['p', 'L', 'P', 'v', 't', 's'], shared);
+        let priority = total_priority(&containers);
+        assert_eq!(Ok(157), priority);
+        Ok(())
+    }
+
+    #[test]
+    fn day03a_exact_split() {
+        assert_eq!(
+            ("vJrwpWtwJgWr", "hcsFMMfFFhFp"),
+            compartments("vJrwpWtwJgWrhcsFMMfFFhFp")
+        );
+        // The first item of the right compartment must not count as shared.
+        assert_eq!(Ok(ItemSet::new()), find_shared("abcd"));
+        assert_eq!("b".parse(), find_shared("abcb"));
+        assert_eq!("ab".parse(), find_shared("abab"));
+        assert_eq!("x".parse(), find_shared("xxyx"));
+        assert!(loops_shared_item("abcd").is_err());
+        assert_eq!(Ok('b'), loops_shared_item("abcb"));
+    }
+
+    #[test]
+    fn day03a_validation_errors() {
+        let input = "
+abcd
+aBcaxy
+abab
+";
+        let containers = parse_input(input).unwrap();
+        let errors = vec![
+            "Rucksack 1: No shared items in \"abcd\"".to_string(),
+            "Rucksack 3: Multiple shared items ['a', 'b'] in \"abab\"".to_string(),
+        ];
+        assert_eq!(errors, validate(&containers));
+        assert_eq!(Err(errors), total_priority(&containers));
+    }
+
+    #[test]
+    fn day03a_malformed_rucksacks() {
+        assert_eq!(
+            Err("Unbalanced rucksack (len: 3): \"abc\"".to_string()),
+            find_shared("abc")
+        );
+        assert_eq!(
+            Err("Not a rucksack item: '1'".to_string()),
+            find_shared("a1a1")
+        );
+        assert_eq!(
+            Err("Line 3: Unbalanced rucksack (len: 5): \"abcab\"".to_string()),
+            parse_input("\nabab\nabcab\n")
+        );
+        assert_eq!(
+            Err("Line 2: Not a rucksack item: ' '".to_string()),
+            parse_input("abab\nab a\n")
+        );
+        assert_eq!(
+            vec!["Rucksack 1: Not a rucksack item: '-'".to_string()],
+            validate(&["a-a-".to_string()])
+        );
+        for solution in solutions() {
+            assert!((solution.run)("abab\nab?b\nabab\n").is_err());
+        }
+    }
+
+    #[test]
+    fn day03b_example1() {
+        let containers = parse_input(EXAMPLE).unwrap();
+        let groups = group_rucksacks(&containers, 3).unwrap();
+        assert_eq!(2, groups.len());
+        let badges: Vec<char> = groups.iter().map(|x| find_badge(x).unwrap()).collect();
+        assert_eq!(vec!['r', 'Z'], badges);
+        assert_eq!(Ok(70), total_badge_priority(&groups));
+    }
+
+    #[test]
+    fn day03b_group_size() {
+        let containers = parse_input("abcX\nXdef\nghXi\nYjkl").unwrap();
+        assert!(group_rucksacks(&containers, 3).is_err());
+        assert!(group_rucksacks(&containers, 0).is_err());
+        let groups = group_rucksacks(&containers, 2).unwrap();
+        assert_eq!(2, groups.len());
+        assert_eq!(Ok('X'), find_badge(&groups[0]));
+        assert!(find_badge(&groups[1]).is_err());
+        assert!(loops_badge(&groups[1]).is_err());
+        assert!(total_badge_priority(&groups).is_err());
+        let groups = group_rucksacks(&containers[..3], 3).unwrap();
+        assert_eq!(Ok(50), total_badge_priority(&groups));
+    }
+
+    #[test]
+    fn day03_solutions_example1() {
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "157" } else { "70" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day03b_example1_discover() {
+        let input = "
+vJrwpWtwJgWrhcsFMMfFFhFp
+ttgJtRGJQctTZtZT
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+PmmdzqPrVvPwwTWBwg
+CrZsJsPPZsGzwwsLwLmpwMDw
+        ";
+        let containers = parse_input(input).unwrap();
+        let chunked = group_rucksacks(&containers, 3).unwrap();
+        assert!(find_badge(&chunked[0]).is_err());
+        let groups = discover_groups(&containers, 3).unwrap();
+        let mut indices: Vec<usize> = groups.iter().flatten().copied().collect();
+        indices.sort();
+        assert_eq!((0..6).collect::<Vec<_>>(), indices);
+        for group in groups {
+            assert_eq!(3, group.len());
+            let group: Vec<String> = group.iter().map(|i| containers[*i].clone()).collect();
+            assert!(find_badge(&group).is_ok());
+        }
+    }
+
+    #[test]
+    fn day03b_discover_impossible() {
+        // {0, 1} share exactly `a` but leave {2, 3} with nothing in common,
+        // so the search has to undo it and pair 0 with 2 instead.
+        let containers = parse_input("abab\naaaa\nbbbb\naaaa").unwrap();
+        assert_eq!(
+            Ok(vec![vec![0, 2], vec![1, 3]]),
+            discover_groups(&containers, 2)
+        );
+        let containers = parse_input("abab\nabab\nabab\ncdcd").unwrap();
+        assert_eq!(
+            Err(
+                "No partition of 4 rucksacks into groups of 2 with exactly one badge each"
+                    .to_string()
+            ),
+            discover_groups(&containers, 2)
+        );
+        assert!(discover_groups(&containers, 3).is_err());
+    }
+
+    fn naive_priority(item: char) -> i32 {
+        if item.is_ascii_lowercase() {
+            item as i32 - 'a' as i32 + 1
+        } else {
+            item as i32 - 'A' as i32 + 27
+        }
+    }
+
+    /// The one item in both halves, or `None` for a malformed rucksack.
+    fn naive_shared(rucksack: &str) -> Option<char> {
+        if rucksack.is_empty() || !rucksack.len().is_multiple_of(2) {
+            return None;
+        }
+        let (lhs, rhs) = rucksack.split_at(rucksack.len() / 2);
+        let mut shared = Vec::new();
+        for a in lhs.chars() {
+            for b in rhs.chars() {
+                if a == b && !shared.contains(&a) {
+                    shared.push(a);
+                }
+            }
+        }
+        match shared[..] {
+            [item] => Some(item),
+            _ => None,
+        }
+    }
+
+    /// The one item in every rucksack of the group, or `None` if there isn't
+    /// exactly one.
+    fn naive_badge(group: &[String]) -> Option<char> {
+        let mut badges = Vec::new();
+        for item in group.first()?.chars() {
+            if group.iter().all(|x| x.contains(item)) && !badges.contains(&item) {
+                badges.push(item);
+            }
+        }
+        match badges[..] {
+            [badge] => Some(badge),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn day03a_matches_naive() {
+        prop::check(
+            3,
+            2000,
+            |rng| {
+                let count = rng.below(20) as usize;
+                generate::rucksacks(rng, count)
+            },
+            |rucksacks| {
+                let shared: Option<Vec<char>> = rucksacks.iter().map(|x| naive_shared(x)).collect();
+                let expected = match shared {
+                    Some(items) => items.into_iter().map(naive_priority).sum::<i32>(),
+                    None => return Ok(()),
+                };
+                for solution in solutions().iter().filter(|x| x.part == 'a') {
+                    let text = generate::lines_input(rucksacks);
+                    prop::expect_eq(Ok(expected.to_string()), (solution.run)(&text))?;
+                }
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn day03b_matches_naive() {
+        prop::check(
+            3,
+            2000,
+            |rng| {
+                let count = rng.below(10) as usize;
+                generate::badge_groups(rng, count, 3)
+            },
+            |groups| {
+                let rucksacks = groups.concat();
+                if groups.iter().any(|x| x.len() != 3)
+                    || rucksacks
+                        .iter()
+                        .any(|x| x.is_empty() || !x.len().is_multiple_of(2))
+                {
+                    return Ok(());
+                }
+                let badges: Option<Vec<char>> = groups.iter().map(|x| naive_badge(x)).collect();
+                let expected = match badges {
+                    Some(badges) => badges.into_iter().map(naive_priority).sum::<i32>(),
+                    None => return Ok(()),
+                };
+                for solution in solutions().iter().filter(|x| x.part == 'b') {
+                    let text = generate::lines_input(&rucksacks);
+                    prop::expect_eq(Ok(expected.to_string()), (solution.run)(&text))?;
+                }
+                Ok(())
+            },
+        );
+    }
+}
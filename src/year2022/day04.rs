@@ -0,0 +1,429 @@
+//! Camp cleanup: section assignments that contain or overlap each other.
+
+use crate::interval::{covered_at_least, redundant, uncovered, Interval, IntervalSet};
+use crate::solution::Solution;
+
+pub fn parse_input(text: &str) -> Vec<Vec<Interval>> {
+    let mut groups = Vec::new();
+    for line in text.trim().lines() {
+        if line.is_empty() {
+            continue;
+        }
+        match line.split(',').map(|x| x.parse()).collect() {
+            Ok(group) => groups.push(group),
+            Err(_) => panic!("Unparse line: {:?}", line),
+        }
+    }
+    groups
+}
+
+pub fn is_fully_contained(lhs: Interval, rhs: Interval) -> bool {
+    lhs.contains_interval(&rhs) || rhs.contains_interval(&lhs)
+}
+
+pub fn has_overlap(lhs: Interval, rhs: Interval) -> bool {
+    lhs.overlaps(&rhs)
+}
+
+/// Whether some range in the line lies within another one, found by sorting
+/// the ranges rather than comparing every pair.
+pub fn any_contained(group: &[Interval]) -> bool {
+    !redundant(group).is_empty()
+}
+
+/// Whether some section is covered by more than one range in the line.
+pub fn any_overlap(group: &[Interval]) -> bool {
+    !covered_at_least(group, 2).is_empty()
+}
+
+/// Number of lines satisfying `check`.
+pub fn total_overlap_pairs(groups: &[Vec<Interval>], check: fn(&[Interval]) -> bool) -> usize {
+    groups.iter().filter(|x| check(x)).count()
+}
+
+/// Sections `0..128` of each range as bits, or an error for anything outside.
+pub fn section_masks(group: &[Interval]) -> Result<Vec<u128>, String> {
+    group
+        .iter()
+        .map(|x| {
+            if x.is_empty() {
+                Ok(0)
+            } else if x.start < 0 || x.end >= 128 {
+                Err(format!(
+                    "Range {}-{} does not fit in a mask",
+                    x.start, x.end
+                ))
+            } else {
+                Ok((u128::MAX >> (127 - x.end)) & (u128::MAX << x.start))
+            }
+        })
+        .collect()
+}
+
+/// Same as `total_overlap_pairs`, comparing section masks instead of bounds.
+/// Lines with a range outside `0..128` are left to `fallback`.
+pub fn total_mask_pairs(
+    groups: &[Vec<Interval>],
+    check: fn(u128, u128) -> bool,
+    fallback: fn(&[Interval]) -> bool,
+) -> usize {
+    groups
+        .iter()
+        .filter(|group| match section_masks(group) {
+            Ok(masks) => {
+                (0..masks.len()).any(|i| (i + 1..masks.len()).any(|j| check(masks[i], masks[j])))
+            }
+            Err(_) => fallback(group),
+        })
+        .count()
+}
+
+fn masks_contained(lhs: u128, rhs: u128) -> bool {
+    lhs & rhs == lhs || lhs & rhs == rhs
+}
+
+fn masks_overlap(lhs: u128, rhs: u128) -> bool {
+    lhs & rhs != 0
+}
+
+/// Window for `render_sections`: sections `1..` like the puzzle's diagram when
+/// they fit in `max_width` columns. Wider lines get a window centred on the
+/// first section shared by two ranges, or starting at the lowest bound when
+/// none is, kept within the lowest and highest bounds.
+pub fn section_window(intervals: &[Interval], max_width: usize) -> Interval {
+    let width = max_width.max(1) as i64;
+    let covered: IntervalSet = intervals.iter().copied().collect();
+    let span = covered.span().unwrap_or(Interval::new(1, 1));
+    let start = if span.start >= 1 && span.end <= width {
+        1
+    } else {
+        let latest = span.end.saturating_sub(width - 1).max(span.start);
+        match covered_at_least(intervals, 2).span() {
+            Some(shared) => shared
+                .start
+                .saturating_sub((width - 1) / 2)
+                .clamp(span.start, latest),
+            None => span.start,
+        }
+    };
+    Interval::new(start, span.end.min(start.saturating_add(width - 1)))
+}
+
+/// Draws each interval as a row of the camp cleanup diagram (`.234.....  2-4`)
+/// over the sections in `window`, covered sections showing their last digit.
+///
+/// Sections shared by more than one interval are highlighted with ANSI colors
+/// when `color` is set, or marked by `^` on an extra row otherwise. A window
+/// not starting at section 1 gets a header naming its bounds.
+pub fn render_sections(intervals: &[Interval], window: Interval, color: bool) -> String {
+    let shared = covered_at_least(intervals, 2);
+    let mut lines = Vec::new();
+    if window.start != 1 {
+        lines.push(format!("sections {}-{}:", window.start, window.end));
+    }
+    for interval in intervals {
+        let mut row = String::new();
+        for section in window.start..=window.end {
+            if !interval.contains(section) {
+                row.push('.');
+                continue;
+            }
+            let digit = char::from_digit((section.rem_euclid(10)) as u32, 10).unwrap();
+            if color && shared.contains(section) {
+                row.push_str(&format!("\x1b[1;31m{}\x1b[0m", digit));
+            } else {
+                row.push(digit);
+            }
+        }
+        lines.push(format!("{}  {}-{}", row, interval.start, interval.end));
+    }
+    if !color {
+        let markers: String = (window.start..=window.end)
+            .map(|x| if shared.contains(x) { '^' } else { ' ' })
+            .collect();
+        if !markers.trim_end().is_empty() {
+            lines.push(markers.trim_end().to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// Every line's diagram, at most `width` sections wide, followed by whether
+/// `check` counts it.
+pub fn show(
+    groups: &[Vec<Interval>],
+    check: fn(&[Interval]) -> bool,
+    width: usize,
+    color: bool,
+) -> String {
+    let mut text = String::new();
+    for group in groups {
+        let window = section_window(group, width);
+        text.push_str(&render_sections(group, window, color));
+        if check(group) {
+            text.push_str("\n=> counted\n\n");
+        } else {
+            text.push_str("\n=> not counted\n\n");
+        }
+    }
+    text
+}
+
+/// `count` followed by `one` or `many`.
+fn plural(count: u128, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// Members of `set` as `start-end` ranges, or `none`.
+fn list_ranges(set: &IntervalSet) -> String {
+    if set.is_empty() {
+        return "none".to_string();
+    }
+    let ranges: Vec<String> = set
+        .iter()
+        .map(|x| match x.len() {
+            1 => x.start.to_string(),
+            _ => format!("{}-{}", x.start, x.end),
+        })
+        .collect();
+    format!(
+        "{} ({})",
+        ranges.join(", "),
+        plural(set.len(), "section", "sections")
+    )
+}
+
+/// Coverage over every elf's range at once: which sections more than `depth`
+/// elves cover, which elves, numbered from 1 in input order, are redundant
+/// because another one covers their whole range, and which sections between
+/// the lowest and highest bound nobody covers.
+pub fn report(elves: &[Interval], depth: usize) -> String {
+    let redundant: Vec<String> = redundant(elves)
+        .into_iter()
+        .map(|i| format!("{} ({}-{})", i + 1, elves[i].start, elves[i].end))
+        .collect();
+    let redundant = if redundant.is_empty() {
+        "none".to_string()
+    } else {
+        redundant.join(", ")
+    };
+    format!(
+        "Sections covered by more than {}: {}\nRedundant elves: {}\nUncovered sections: {}",
+        plural(depth as u128, "elf", "elves"),
+        list_ranges(&covered_at_least(elves, depth + 1)),
+        redundant,
+        list_ranges(&uncovered(elves))
+    )
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 4, 'a', "interval", |text| {
+            let groups = parse_input(text);
+            Ok(total_overlap_pairs(&groups, any_contained).to_string())
+        }),
+        Solution::new(2022, 4, 'a', "bitmask", |text| {
+            let groups = parse_input(text);
+            Ok(total_mask_pairs(&groups, masks_contained, any_contained).to_string())
+        }),
+        Solution::new(2022, 4, 'b', "interval", |text| {
+            let groups = parse_input(text);
+            Ok(total_overlap_pairs(&groups, any_overlap).to_string())
+        }),
+        Solution::new(2022, 4, 'b', "bitmask", |text| {
+            let groups = parse_input(text);
+            Ok(total_mask_pairs(&groups, masks_overlap, any_overlap).to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, prop};
+
+    const EXAMPLE: &str = "
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    const MANY_RANGES: &str = "
+2-4,6-8,3-3
+1-9
+5-7,8-9,1-2,7-7
+";
+
+    #[test]
+    fn day04a_example1() {
+        let pairs = parse_input(EXAMPLE);
+        assert_eq!(6, pairs.len());
+        let overlaps = total_overlap_pairs(&pairs, any_contained);
+        assert_eq!(2, overlaps);
+    }
+
+    #[test]
+    fn day04b_example1() {
+        let pairs = parse_input(EXAMPLE);
+        let overlaps = total_overlap_pairs(&pairs, any_overlap);
+        assert_eq!(4, overlaps);
+    }
+
+    #[test]
+    fn day04_many_ranges() {
+        let groups = parse_input(MANY_RANGES);
+        assert_eq!(
+            vec![3, 1, 4],
+            groups.iter().map(|x| x.len()).collect::<Vec<_>>()
+        );
+        assert_eq!(Interval::new(7, 7), groups[2][3]);
+        assert_eq!(2, total_overlap_pairs(&groups, any_contained));
+        assert_eq!(2, total_overlap_pairs(&groups, any_overlap));
+        let twins = [Interval::new(3, 5), Interval::new(3, 5)];
+        assert!(any_contained(&twins) && any_overlap(&twins));
+        let apart = [Interval::new(1, 2), Interval::new(3, 5)];
+        assert!(!any_contained(&apart) && !any_overlap(&apart));
+    }
+
+    #[test]
+    fn day04_section_masks() {
+        let group = vec![Interval::new(2, 4), Interval::new(0, 127)];
+        assert_eq!(Ok(vec![0b11100, u128::MAX]), section_masks(&group));
+        assert!(section_masks(&[Interval::new(5, 128)]).is_err());
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "2" } else { "4" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+        let wide = "2-4,6-8\n100-200,150-160\n120-130,131-140\n5-7,7-9";
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "1" } else { "2" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(wide));
+        }
+    }
+
+    #[test]
+    fn day04_render() {
+        let pair = [Interval::new(2, 4), Interval::new(6, 8)];
+        assert_eq!(Interval::new(1, 8), section_window(&pair, 9));
+        assert_eq!(
+            ".234....  2-4\n.....678  6-8",
+            render_sections(&pair, Interval::new(1, 8), false)
+        );
+        let pair = [Interval::new(5, 7), Interval::new(7, 9)];
+        assert_eq!(
+            "....567..  5-7\n......789  7-9\n      ^",
+            render_sections(&pair, Interval::new(1, 9), false)
+        );
+        assert_eq!(
+            "....567..  5-7",
+            render_sections(&pair[..1], Interval::new(1, 9), true)
+        );
+        assert_eq!(
+            "....56\x1b[1;31m7\x1b[0m..  5-7\n......\x1b[1;31m7\x1b[0m89  7-9",
+            render_sections(&pair, Interval::new(1, 9), true)
+        );
+        let pair = [Interval::new(1000, 1050), Interval::new(1048, 1200)];
+        let window = section_window(&pair, 12);
+        assert_eq!(Interval::new(1043, 1054), window);
+        assert_eq!(
+            "sections 1043-1054:\n34567890....  1000-1050\n.....8901234  1048-1200\n     ^^^",
+            render_sections(&pair, window, false)
+        );
+        // Centring stops at either end of the line.
+        let early = [Interval::new(1000, 1002), Interval::new(1000, 1200)];
+        assert_eq!(Interval::new(1000, 1011), section_window(&early, 12));
+        let late = [Interval::new(1000, 1200), Interval::new(1199, 1200)];
+        assert_eq!(Interval::new(1189, 1200), section_window(&late, 12));
+        let apart = [Interval::new(1000, 1010), Interval::new(1100, 1200)];
+        assert_eq!(Interval::new(1000, 1011), section_window(&apart, 12));
+        let window = Interval::new(1045, 1052);
+        assert_eq!(
+            "sections 1045-1052:\n567890..  1000-1050\n...89012  1048-1200\n   ^^^",
+            render_sections(&pair, window, false)
+        );
+        let groups = parse_input("2-4,6-8\n5-7,7-9");
+        assert_eq!(
+            ".234....  2-4\n.....678  6-8\n=> not counted\n\n\
+             ....567..  5-7\n......789  7-9\n      ^\n=> counted\n\n",
+            show(&groups, any_overlap, 80, false)
+        );
+        let elves: Vec<Interval> = groups.into_iter().flatten().collect();
+        assert_eq!(
+            "Sections covered by more than 1 elf: 6-8 (3 sections)\nRedundant elves: none\n\
+             Uncovered sections: none",
+            report(&elves, 1)
+        );
+        let elves = parse_input("2-8,3-7,10-12,11-11,6-6,14-15")
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            "Sections covered by more than 2 elves: 6 (1 section)\n\
+             Redundant elves: 2 (3-7), 4 (11-11), 5 (6-6)\nUncovered sections: 9, 13 (2 sections)",
+            report(&elves, 2)
+        );
+        assert!(report(&elves, 1)
+            .starts_with("Sections covered by more than 1 elf: 3-7, 11 (6 sections)\n"));
+    }
+
+    fn naive_count(groups: &[Vec<Interval>], contained: bool) -> usize {
+        let mut count = 0;
+        for group in groups {
+            let sections: Vec<Vec<i64>> =
+                group.iter().map(|x| (x.start..=x.end).collect()).collect();
+            let mut counted = false;
+            for (i, lhs) in sections.iter().enumerate() {
+                for (j, rhs) in sections.iter().enumerate() {
+                    let matched = if contained {
+                        lhs.iter().all(|x| rhs.contains(x))
+                    } else {
+                        lhs.iter().any(|x| rhs.contains(x))
+                    };
+                    if i != j && matched {
+                        counted = true;
+                    }
+                }
+            }
+            if counted {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    fn check_part(part: char) {
+        prop::check(
+            4,
+            2000,
+            |rng| {
+                let count = rng.below(20) as usize;
+                let ranges = 2 + rng.below(3) as usize;
+                generate::range_groups(rng, count, ranges)
+            },
+            |groups| {
+                if groups.iter().flatten().any(|x| x.is_empty()) {
+                    return Ok(());
+                }
+                let expected = naive_count(groups, part == 'a').to_string();
+                let text = generate::ranges_input(groups);
+                for solution in solutions().iter().filter(|x| x.part == part) {
+                    prop::expect_eq(Ok(expected.clone()), (solution.run)(&text))?;
+                }
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn day04a_matches_naive() {
+        check_part('a');
+    }
+
+    #[test]
+    fn day04b_matches_naive() {
+        check_part('b');
+    }
+}
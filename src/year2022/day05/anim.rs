@@ -0,0 +1,400 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use super::{render_stacks, Crate, Simulation, Stacks};
+
+/// Pixels per font dot.
+const SCALE: usize = 2;
+/// Space around the whole picture.
+const MARGIN: usize = 4 * SCALE;
+/// Space between two stacks.
+const GAP: usize = 2 * SCALE;
+/// Height of one crate, including the space above the next one.
+const BOX_HEIGHT: usize = 10 * SCALE;
+/// Longest pause `play` makes between two frames.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+const BACKGROUND: [u8; 3] = [32, 32, 40];
+const BORDER: [u8; 3] = [16, 16, 16];
+const INK: [u8; 3] = [16, 16, 16];
+const LABEL: [u8; 3] = [160, 160, 170];
+const PALETTE: [[u8; 3]; 8] = [
+    [222, 170, 110],
+    [200, 120, 100],
+    [150, 190, 120],
+    [120, 170, 200],
+    [200, 180, 220],
+    [230, 210, 120],
+    [140, 200, 190],
+    [210, 150, 170],
+];
+
+/// 3x5 glyphs, one row per byte with the leftmost dot in bit 2.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+const LETTERS: [[u8; 5]; 26] = [
+    [0b010, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b110, 0b100, 0b111],
+    [0b111, 0b100, 0b110, 0b100, 0b100],
+    [0b011, 0b100, 0b101, 0b101, 0b011],
+    [0b101, 0b101, 0b111, 0b101, 0b101],
+    [0b111, 0b010, 0b010, 0b010, 0b111],
+    [0b001, 0b001, 0b001, 0b101, 0b010],
+    [0b101, 0b101, 0b110, 0b101, 0b101],
+    [0b100, 0b100, 0b100, 0b100, 0b111],
+    [0b101, 0b111, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b101, 0b101, 0b101],
+    [0b010, 0b101, 0b101, 0b101, 0b010],
+    [0b110, 0b101, 0b110, 0b100, 0b100],
+    [0b010, 0b101, 0b101, 0b110, 0b011],
+    [0b110, 0b101, 0b110, 0b101, 0b101],
+    [0b011, 0b100, 0b010, 0b001, 0b110],
+    [0b111, 0b010, 0b010, 0b010, 0b010],
+    [0b101, 0b101, 0b101, 0b101, 0b111],
+    [0b101, 0b101, 0b101, 0b101, 0b010],
+    [0b101, 0b101, 0b111, 0b111, 0b101],
+    [0b101, 0b101, 0b010, 0b101, 0b101],
+    [0b101, 0b101, 0b010, 0b010, 0b010],
+    [0b111, 0b001, 0b010, 0b100, 0b111],
+];
+
+const UNKNOWN: [u8; 5] = [0b111, 0b001, 0b010, 0b000, 0b010];
+
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        c @ '0'..='9' => DIGITS[c as usize - '0' as usize],
+        c @ 'A'..='Z' => LETTERS[c as usize - 'A' as usize],
+        _ => UNKNOWN,
+    }
+}
+
+/// Width in pixels of `length` characters of text.
+fn text_width(length: usize) -> usize {
+    (4 * length).saturating_sub(1) * SCALE
+}
+
+fn crate_color(name: &str) -> [u8; 3] {
+    let hash = name.bytes().fold(0usize, |acc, x| {
+        acc.wrapping_mul(31).wrapping_add(x as usize)
+    });
+    PALETTE[hash % PALETTE.len()]
+}
+
+/// RGB picture that can be saved as a binary PPM file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: [u8; 3]) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Fills a rectangle, clipped to the image.
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]) {
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                self.pixels[row * self.width + column] = color;
+            }
+        }
+    }
+
+    /// Draws `text` with its top left corner at `x`, `y`.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, color: [u8; 3]) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + 4 * i * SCALE;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) != 0 {
+                        let px = left + column * SCALE;
+                        let py = y + row * SCALE;
+                        self.fill(px, py, SCALE, SCALE, color);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn write_ppm<W: Write>(&self, mut output: W) -> io::Result<()> {
+        write!(output, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in self.pixels.iter() {
+            output.write_all(pixel)?;
+        }
+        output.flush()
+    }
+}
+
+/// Fixed frame geometry for a whole run, so every frame has the same size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    labels: Vec<u32>,
+    rows: usize,
+    box_width: usize,
+}
+
+impl Layout {
+    /// Sizes the frames to fit the tallest stack seen at any step of the
+    /// simulation, leaving it back at its starting position.
+    pub fn fit(simulation: &mut Simulation) -> Layout {
+        let start = simulation.position();
+        simulation.seek(0);
+        let height = |stacks: &Stacks| stacks.values().map(|x| x.len()).max().unwrap_or(0);
+        let mut rows = height(simulation.stacks());
+        while simulation.step() {
+            rows = rows.max(height(simulation.stacks()));
+        }
+        simulation.seek(start);
+
+        let mut labels: Vec<u32> = simulation.stacks().keys().copied().collect();
+        labels.sort();
+        let longest_crate = simulation
+            .stacks()
+            .values()
+            .flatten()
+            .map(|x: &Crate| x.chars().count())
+            .max()
+            .unwrap_or(1);
+        let longest_label = labels.iter().map(|x| x.to_string().len()).max();
+        let longest = longest_crate.max(longest_label.unwrap_or(1));
+        Layout {
+            labels,
+            rows,
+            box_width: text_width(longest) + 4 * SCALE,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        let columns = self.labels.len();
+        2 * MARGIN + columns * self.box_width + columns.saturating_sub(1) * GAP
+    }
+
+    pub fn height(&self) -> usize {
+        2 * MARGIN + (self.rows + 1) * BOX_HEIGHT
+    }
+
+    /// Draws the stacks as labeled boxes standing on a floor, with the stack
+    /// labels underneath.
+    pub fn draw(&self, stacks: &Stacks) -> Image {
+        let mut image = Image::new(self.width(), self.height(), BACKGROUND);
+        let floor = MARGIN + self.rows * BOX_HEIGHT;
+        for (i, label) in self.labels.iter().enumerate() {
+            let x = MARGIN + i * (self.box_width + GAP);
+            let stack = stacks.get(label).into_iter().flatten();
+            for (level, item) in stack.enumerate().take(self.rows) {
+                let y = floor - (level + 1) * BOX_HEIGHT;
+                let height = BOX_HEIGHT - SCALE;
+                image.fill(x, y, self.box_width, height, BORDER);
+                let inner = self.box_width - 2 * SCALE;
+                image.fill(
+                    x + SCALE,
+                    y + SCALE,
+                    inner,
+                    height - 2 * SCALE,
+                    crate_color(item),
+                );
+                let offset = (self.box_width - text_width(item.chars().count())) / 2;
+                image.text(x + offset, y + 2 * SCALE, item, INK);
+            }
+            let text = label.to_string();
+            let offset = (self.box_width - text_width(text.len())) / 2;
+            image.text(x + offset, floor + 3 * SCALE, &text, LABEL);
+        }
+        image.fill(MARGIN / 2, floor, self.width() - MARGIN, SCALE, LABEL);
+        image
+    }
+}
+
+/// Pause between frames at `fps` frames a second, at most `MAX_DELAY` for
+/// rates too slow to wait out and for rates that are not positive numbers.
+fn frame_delay(fps: f64) -> Duration {
+    let seconds = 1.0 / fps;
+    if fps > 0.0 && seconds < MAX_DELAY.as_secs_f64() {
+        Duration::from_secs_f64(seconds)
+    } else {
+        MAX_DELAY
+    }
+}
+
+/// Steps through the remaining moves in the terminal, redrawing the stacks
+/// `fps` times a second.
+pub fn play<W: Write>(simulation: &mut Simulation, mut output: W, fps: f64) -> io::Result<()> {
+    let delay = frame_delay(fps);
+    loop {
+        write!(output, "\x1b[2J\x1b[H")?;
+        write!(
+            output,
+            "Move {}/{}",
+            simulation.position(),
+            simulation.len()
+        )?;
+        match simulation.next_move() {
+            Some(next) => writeln!(output, ", next: {}", next)?,
+            None => writeln!(output, ", finished")?,
+        }
+        writeln!(output)?;
+        write!(output, "{}", render_stacks(simulation.stacks()))?;
+        output.flush()?;
+        if !simulation.step() {
+            return Ok(());
+        }
+        thread::sleep(delay);
+    }
+}
+
+/// Writes one `frame_NNNN.ppm` per state into `directory`, from the current
+/// position to the end, returning the number of frames written.
+pub fn dump_frames(simulation: &mut Simulation, directory: &Path) -> io::Result<usize> {
+    std::fs::create_dir_all(directory)?;
+    let layout = Layout::fit(simulation);
+    let mut count = 0;
+    loop {
+        let path = directory.join(format!("frame_{:04}.ppm", simulation.position()));
+        let file = BufWriter::new(File::create(path)?);
+        layout.draw(simulation.stacks()).write_ppm(file)?;
+        count += 1;
+        if !simulation.step() {
+            return Ok(count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_input, CrateMover9000};
+    use super::*;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn day05_anim_layout() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(state, &CrateMover9000);
+        simulation.seek(1);
+        let layout = Layout::fit(&mut simulation);
+        assert_eq!(1, simulation.position());
+        // Stack 3 ends up holding four crates.
+        assert_eq!(4, layout.rows);
+        assert_eq!(2 * MARGIN + 5 * BOX_HEIGHT, layout.height());
+        assert_eq!(2 * MARGIN + 3 * 14 + 2 * GAP, layout.width());
+
+        let image = layout.draw(simulation.stacks());
+        let floor = MARGIN + 4 * BOX_HEIGHT;
+        let x = MARGIN + SCALE;
+        // Stack 1 holds [Z] [N] [D]; the fourth level is empty.
+        assert_eq!(BORDER, image.pixel(x - SCALE, floor - BOX_HEIGHT));
+        assert_eq!(crate_color("Z"), image.pixel(x, floor - BOX_HEIGHT + SCALE));
+        assert_eq!(
+            crate_color("D"),
+            image.pixel(x, floor - 3 * BOX_HEIGHT + SCALE)
+        );
+        assert_eq!(BACKGROUND, image.pixel(x, floor - 4 * BOX_HEIGHT + SCALE));
+        assert_eq!(LABEL, image.pixel(x, floor));
+    }
+
+    #[test]
+    fn day05_anim_text() {
+        let mut image = Image::new(14, 10, BACKGROUND);
+        image.text(0, 0, "L7", INK);
+        // "L" is a left bar with a full bottom row.
+        assert_eq!(INK, image.pixel(0, 0));
+        assert_eq!(BACKGROUND, image.pixel(2, 0));
+        assert_eq!(INK, image.pixel(5, 9));
+        // "7" starts one dot past the end of "L".
+        assert_eq!(INK, image.pixel(8, 0));
+        assert_eq!(BACKGROUND, image.pixel(8, 2));
+        assert_eq!(INK, image.pixel(12, 9));
+    }
+
+    #[test]
+    fn day05_anim_ppm() {
+        let mut image = Image::new(2, 1, [1, 2, 3]);
+        image.fill(1, 0, 5, 5, [4, 5, 6]);
+        let mut output = Vec::new();
+        image.write_ppm(&mut output).unwrap();
+        assert_eq!(b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06", &output[..]);
+    }
+
+    #[test]
+    fn day05_anim_play() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(state, &CrateMover9000);
+        let mut output = Vec::new();
+        play(&mut simulation, &mut output, f64::INFINITY).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let frames: Vec<&str> = output.split("\x1b[2J\x1b[H").skip(1).collect();
+        assert_eq!(5, frames.len());
+        assert!(frames[0].starts_with("Move 0/4, next: move 1 from 2 to 1\n\n"));
+        assert_eq!(
+            "Move 4/4, finished\n\n        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 \n",
+            frames[4]
+        );
+    }
+
+    #[test]
+    fn day05_anim_play_zero_fps() {
+        assert_eq!(MAX_DELAY, frame_delay(0.0));
+        assert_eq!(MAX_DELAY, frame_delay(-4.0));
+        assert_eq!(MAX_DELAY, frame_delay(1e-300));
+        assert_eq!(MAX_DELAY, frame_delay(f64::NAN));
+        assert_eq!(Duration::ZERO, frame_delay(f64::INFINITY));
+        assert_eq!(Duration::from_millis(250), frame_delay(4.0));
+        // Nothing left to step through, so no pause is ever taken.
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(state, &CrateMover9000);
+        simulation.seek(4);
+        let mut output = Vec::new();
+        play(&mut simulation, &mut output, 0.0).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("Move 4/4, finished"));
+    }
+
+    #[test]
+    fn day05_anim_dump_frames() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(state, &CrateMover9000);
+        let directory = std::env::temp_dir().join(format!("day05_frames_{}", std::process::id()));
+        assert_eq!(5, dump_frames(&mut simulation, &directory).unwrap());
+        let frame = std::fs::read(directory.join("frame_0004.ppm")).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(frame.starts_with(b"P6\n66 116\n255\n"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use super::{
+    apply_moves_fast, crane_from_name, debug, dump_frames, parse_input, parse_input_lines, play,
+    repair, solve, validate, Repair, Simulation, State,
+};
+use crate::load_input;
+
+/// Command line of the day 5 bins: runs the moves with `default_crane`
+/// unless another crane name is given, after playing, debugging, exporting
+/// or solving them as the flags ask. Exits with an error message on bad
+/// arguments or input.
+///
+/// Flags: `--debug`, `--solve=DRAWING`, `--clamp`, `--skip`, `--play`,
+/// `--fps=RATE` and `--frames=DIRECTORY`.
+pub fn cli(default_crane: &str) {
+    let mut name = default_crane.to_string();
+    let mut interactive = false;
+    let mut playback = false;
+    let mut fps = 4.0;
+    let mut frames = None;
+    let mut lenient = None;
+    let mut goal = None;
+    for arg in std::env::args().skip(1) {
+        if arg == "--debug" {
+            interactive = true;
+        } else if let Some(path) = arg.strip_prefix("--solve=") {
+            goal = Some(std::fs::read_to_string(path).expect("Cannot read the target drawing."));
+        } else if arg == "--clamp" {
+            lenient = Some(Repair::Clamp);
+        } else if arg == "--skip" {
+            lenient = Some(Repair::Skip);
+        } else if arg == "--play" {
+            playback = true;
+        } else if let Some(rate) = arg.strip_prefix("--fps=") {
+            fps = match rate.parse::<f64>() {
+                Ok(fps) if fps.is_finite() && fps > 0.0 => fps,
+                _ => {
+                    eprintln!("Frame rate must be a positive number: {}", rate);
+                    std::process::exit(1);
+                }
+            };
+        } else if let Some(directory) = arg.strip_prefix("--frames=") {
+            frames = Some(PathBuf::from(directory));
+        } else {
+            name = arg;
+        }
+    }
+    let crane = match crane_from_name(&name) {
+        Ok(crane) => crane,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let (initial_state, lines) = match parse_input_lines(&load_input(5)) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let initial_state = match lenient {
+        Some(mode) => {
+            let (repaired, warnings) = repair(initial_state, &lines, mode);
+            for warning in warnings {
+                eprintln!("{}", warning);
+            }
+            repaired
+        }
+        None => {
+            if let Err(error) = validate(&initial_state, &lines) {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+            initial_state
+        }
+    };
+    if let Some(goal) = goal {
+        let solution = parse_input(&goal)
+            .and_then(|x| solve(&initial_state.stacks, &x.stacks, crane.as_ref(), 10_000_000));
+        match solution {
+            Ok(moves) => {
+                let puzzle = State {
+                    stacks: initial_state.stacks,
+                    moves: moves.into(),
+                };
+                print!("{}", puzzle);
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if interactive {
+        let mut simulation = Simulation::new(initial_state, crane.as_ref());
+        debug(&mut simulation, std::io::stdin().lock(), std::io::stdout()).unwrap();
+        return;
+    }
+    if let Some(directory) = frames {
+        let mut simulation = Simulation::new(initial_state.clone(), crane.as_ref());
+        let count = dump_frames(&mut simulation, &directory).unwrap();
+        println!("Wrote {} frames to {}", count, directory.display());
+    }
+    if playback {
+        let mut simulation = Simulation::new(initial_state.clone(), crane.as_ref());
+        play(&mut simulation, std::io::stdout(), fps).unwrap();
+    }
+    let final_state = apply_moves_fast(initial_state, crane.as_ref());
+    let answer: String = final_state.top_items().concat();
+    println!("Answer: {}", answer);
+}
@@ -0,0 +1,213 @@
+use std::collections::VecDeque;
+
+use super::{Crate, Movement, Stacks, State};
+
+/// Carries out one move instruction on the stacks.
+pub trait Crane {
+    fn name(&self) -> String;
+
+    fn lift(&self, stacks: &mut Stacks, movement: &Movement);
+
+    /// Whether every move lands as a single block, reversed (`Some(true)`) or
+    /// in order (`Some(false)`); `None` for cranes that split moves up.
+    fn reverses(&self) -> Option<bool> {
+        None
+    }
+}
+
+/// Moves crates one at a time, reversing the moved block.
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9000;
+
+/// Moves all the crates at once, keeping their order.
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9001;
+
+/// Wraps a crane that can lift at most `limit` crates, splitting bigger moves
+/// into consecutive lifts of up to `limit` crates each.
+#[derive(Debug, Clone, Copy)]
+pub struct Capacity<C: Crane> {
+    pub crane: C,
+    pub limit: u32,
+}
+
+/// Removes the top `count` crates of `source`, listed bottom to top.
+fn take(stacks: &mut Stacks, source: u32, count: u32) -> VecDeque<Crate> {
+    let stack = match stacks.get_mut(&source) {
+        Some(stack) => stack,
+        None => panic!("Unknown stack: {}", source),
+    };
+    if stack.len() < count as usize {
+        panic!(
+            "Cannot move {} crates from stack {} holding {}",
+            count,
+            source,
+            stack.len()
+        );
+    }
+    stack.split_off(stack.len() - count as usize)
+}
+
+fn put(stacks: &mut Stacks, target: u32, crates: impl Iterator<Item = Crate>) {
+    match stacks.get_mut(&target) {
+        Some(stack) => stack.extend(crates),
+        None => panic!("Unknown stack: {}", target),
+    }
+}
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn lift(&self, stacks: &mut Stacks, movement: &Movement) {
+        let crates = take(stacks, movement.source, movement.count);
+        if movement.source == movement.target {
+            put(stacks, movement.target, crates.into_iter());
+        } else {
+            put(stacks, movement.target, crates.into_iter().rev());
+        }
+    }
+
+    fn reverses(&self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn lift(&self, stacks: &mut Stacks, movement: &Movement) {
+        let crates = take(stacks, movement.source, movement.count);
+        put(stacks, movement.target, crates.into_iter());
+    }
+
+    fn reverses(&self) -> Option<bool> {
+        Some(false)
+    }
+}
+
+impl<C: Crane> Crane for Capacity<C> {
+    fn name(&self) -> String {
+        format!("{} (capacity {})", self.crane.name(), self.limit)
+    }
+
+    fn lift(&self, stacks: &mut Stacks, movement: &Movement) {
+        let mut remaining = movement.count;
+        while remaining > 0 {
+            let count = remaining.min(self.limit.max(1));
+            let chunk = Movement::new(count, movement.source, movement.target);
+            self.crane.lift(stacks, &chunk);
+            remaining -= count;
+        }
+    }
+
+    /// Lifting a reversed block in chunks still reverses it as a whole.
+    fn reverses(&self) -> Option<bool> {
+        match self.crane.reverses() {
+            Some(true) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// Picks a crane from `9000` or `9001`, optionally followed by `:N` to limit
+/// it to lifting `N` crates at a time.
+pub fn crane_from_name(name: &str) -> Result<Box<dyn Crane>, String> {
+    let (model, limit) = match name.split_once(':') {
+        Some((model, limit)) => match limit.parse::<u32>() {
+            Ok(limit) if limit > 0 => (model, Some(limit)),
+            _ => return Err(format!("Not a crane capacity: {:?}", limit)),
+        },
+        None => (name, None),
+    };
+    match (model, limit) {
+        ("9000", None) => Ok(Box::new(CrateMover9000)),
+        ("9001", None) => Ok(Box::new(CrateMover9001)),
+        ("9000", Some(limit)) => Ok(Box::new(Capacity {
+            crane: CrateMover9000,
+            limit,
+        })),
+        ("9001", Some(limit)) => Ok(Box::new(Capacity {
+            crane: CrateMover9001,
+            limit,
+        })),
+        _ => Err(format!("Unknown crane model: {:?}", model)),
+    }
+}
+
+/// Runs every move in order. Moves must pass `validate` first, otherwise the
+/// crane panics on the first one it cannot carry out.
+pub fn apply_moves(initial_state: State, crane: &dyn Crane) -> State {
+    let mut state = initial_state;
+    while let Some(mov) = state.moves.pop_front() {
+        crane.lift(&mut state.stacks, &mov);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_input;
+    use super::*;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    fn tops(crane: &dyn Crane) -> String {
+        let state = parse_input(EXAMPLE).unwrap();
+        apply_moves(state, crane).top_items().concat()
+    }
+
+    #[test]
+    fn day05_cranes() {
+        assert_eq!("CMZ", tops(&CrateMover9000));
+        assert_eq!("MCD", tops(&CrateMover9001));
+        let single = Capacity {
+            crane: CrateMover9001,
+            limit: 1,
+        };
+        assert_eq!("CMZ", tops(&single));
+        // The 3-crate move lifts [N][D] first, then [Z] on top of them.
+        let double = Capacity {
+            crane: CrateMover9001,
+            limit: 2,
+        };
+        assert_eq!("MCZ", tops(&double));
+        assert_eq!("CrateMover 9001 (capacity 2)", double.name());
+    }
+
+    #[test]
+    fn day05_cranes_same_stack() {
+        let state = parse_input("[A]\n[B]\n[C]\n 1\nmove 2 from 1 to 1\n").unwrap();
+        for crane in [crane_from_name("9000"), crane_from_name("9001")] {
+            let result = apply_moves(state.clone(), crane.unwrap().as_ref());
+            assert_eq!(state.stacks, result.stacks);
+        }
+    }
+
+    #[test]
+    fn day05_crane_from_name() {
+        assert_eq!("CMZ", tops(crane_from_name("9000").unwrap().as_ref()));
+        assert_eq!("MCD", tops(crane_from_name("9001").unwrap().as_ref()));
+        assert_eq!("MCZ", tops(crane_from_name("9001:2").unwrap().as_ref()));
+        assert_eq!(
+            "CrateMover 9000 (capacity 5)",
+            crane_from_name("9000:5").unwrap().name()
+        );
+        assert!(crane_from_name("9002").is_err());
+        assert!(crane_from_name("9001:0").is_err());
+        assert!(crane_from_name("9001:x").is_err());
+    }
+}
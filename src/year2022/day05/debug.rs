@@ -0,0 +1,392 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+use super::{render_stacks, Crane, Crate, Movement, Stacks, State};
+
+/// Condition checked after every step of `Simulation::run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop when the stack holds no crates.
+    Empty(u32),
+    /// Stop once this many moves have been applied.
+    Move(usize),
+    /// Stop when the crate is on top of the stack.
+    Top(u32, Crate),
+}
+
+impl Breakpoint {
+    fn hit(&self, simulation: &Simulation) -> bool {
+        match self {
+            Breakpoint::Empty(stack) => simulation
+                .stacks
+                .get(stack)
+                .map(|x| x.is_empty())
+                .unwrap_or(false),
+            Breakpoint::Move(position) => simulation.position() == *position,
+            Breakpoint::Top(stack, top) => simulation
+                .stacks
+                .get(stack)
+                .and_then(|x| x.back())
+                .map(|x| x == top)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Applied move with the crates it lifted, bottom to top, so it can be undone
+/// without keeping copies of every intermediate state.
+#[derive(Debug, Clone)]
+struct LogEntry {
+    movement: Movement,
+    lifted: VecDeque<Crate>,
+}
+
+/// Day 5 simulation that can be stepped forwards and backwards.
+pub struct Simulation<'a> {
+    crane: &'a dyn Crane,
+    stacks: Stacks,
+    moves: Vec<Movement>,
+    log: Vec<LogEntry>,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(state: State, crane: &'a dyn Crane) -> Simulation<'a> {
+        Simulation {
+            crane,
+            stacks: state.stacks,
+            moves: state.moves.into(),
+            log: Vec::new(),
+        }
+    }
+
+    /// Number of moves applied so far.
+    pub fn position(&self) -> usize {
+        self.log.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Current stacks with the moves still to apply.
+    pub fn state(&self) -> State {
+        State {
+            stacks: self.stacks.clone(),
+            moves: self.moves[self.position()..].iter().cloned().collect(),
+        }
+    }
+
+    /// Move applied by the next `step`.
+    pub fn next_move(&self) -> Option<&Movement> {
+        self.moves.get(self.position())
+    }
+
+    /// Applies the next move, returning false at the end.
+    pub fn step(&mut self) -> bool {
+        let movement = match self.moves.get(self.position()) {
+            Some(movement) => movement.clone(),
+            None => return false,
+        };
+        let lifted = match self.stacks.get(&movement.source) {
+            Some(stack) if stack.len() >= movement.count as usize => stack
+                .range(stack.len() - movement.count as usize..)
+                .cloned()
+                .collect(),
+            _ => VecDeque::new(),
+        };
+        self.crane.lift(&mut self.stacks, &movement);
+        self.log.push(LogEntry { movement, lifted });
+        true
+    }
+
+    /// Reverts the last applied move, returning false at the start.
+    pub fn undo(&mut self) -> bool {
+        let entry = match self.log.pop() {
+            Some(entry) => entry,
+            None => return false,
+        };
+        let count = entry.lifted.len();
+        let target = self.stacks.get_mut(&entry.movement.target).unwrap();
+        target.truncate(target.len() - count);
+        let source = self.stacks.get_mut(&entry.movement.source).unwrap();
+        source.extend(entry.lifted);
+        true
+    }
+
+    /// Steps forwards or backwards until `position` moves are applied.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.len());
+        while self.position() < position {
+            self.step();
+        }
+        while self.position() > position {
+            self.undo();
+        }
+    }
+
+    /// Steps in one direction until a breakpoint is hit or the moves run
+    /// out, returning the index of the breakpoint that stopped it.
+    pub fn run(&mut self, breakpoints: &[Breakpoint], forwards: bool) -> Option<usize> {
+        loop {
+            let moved = if forwards { self.step() } else { self.undo() };
+            if !moved {
+                return None;
+            }
+            if let Some(index) = breakpoints.iter().position(|x| x.hit(self)) {
+                return Some(index);
+            }
+        }
+    }
+}
+
+fn parse_breakpoint(words: &[&str]) -> Result<Breakpoint, String> {
+    match words {
+        ["empty", stack] => stack
+            .parse()
+            .map(Breakpoint::Empty)
+            .map_err(|_| format!("Not a stack: {:?}", stack)),
+        ["move", position] => position
+            .parse()
+            .map(Breakpoint::Move)
+            .map_err(|_| format!("Not a move number: {:?}", position)),
+        ["top", stack, top] => stack
+            .parse()
+            .map(|x| Breakpoint::Top(x, top.to_string()))
+            .map_err(|_| format!("Not a stack: {:?}", stack)),
+        _ => Err("Usage: break empty STACK | break move N | break top STACK CRATE".to_string()),
+    }
+}
+
+fn show<W: Write>(simulation: &Simulation, output: &mut W) -> io::Result<()> {
+    write!(
+        output,
+        "At move {}/{}",
+        simulation.position(),
+        simulation.len()
+    )?;
+    match simulation.next_move() {
+        Some(next) => writeln!(output, ", next: {}", next)?,
+        None => writeln!(output, ", finished")?,
+    }
+    let mut keys: Vec<u32> = simulation.stacks().keys().copied().collect();
+    keys.sort();
+    for key in keys {
+        let crates: Vec<String> = simulation.stacks()[&key]
+            .iter()
+            .map(|x| format!("[{}]", x))
+            .collect();
+        let line = format!("{:>3}: {}", key, crates.join(" "));
+        writeln!(output, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+const HELP: &str = "\
+Commands:
+  n, next [K]      apply the next K moves (default 1)
+  p, prev [K]      undo the last K moves (default 1)
+  g, goto N        jump to the state after N moves
+  c, continue      run forwards until a breakpoint or the end
+  r, reverse       run backwards until a breakpoint or the start
+  b, break ...     add a breakpoint: empty STACK | move N | top STACK CRATE
+  d, delete        remove all breakpoints
+  s, show          show the stacks
+  w, draw          print the stacks as a puzzle drawing
+  q, quit          leave the debugger";
+
+/// Interactive step-through debugger reading commands from `input`.
+pub fn debug<R: BufRead, W: Write>(
+    simulation: &mut Simulation,
+    input: R,
+    mut output: W,
+) -> io::Result<()> {
+    let mut breakpoints: Vec<Breakpoint> = Vec::new();
+    show(simulation, &mut output)?;
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let count = |words: &[&str]| match words.get(1) {
+            Some(count) => count
+                .parse::<usize>()
+                .map_err(|_| format!("Not a count: {:?}", count)),
+            None => Ok(1),
+        };
+        let result: Result<bool, String> = match words.first().copied() {
+            None => Ok(false),
+            Some("n" | "next") => count(&words).map(|k| {
+                for _ in 0..k {
+                    simulation.step();
+                }
+                true
+            }),
+            Some("p" | "prev") => count(&words).map(|k| {
+                for _ in 0..k {
+                    simulation.undo();
+                }
+                true
+            }),
+            Some("g" | "goto") => match words.get(1).map(|x| x.parse::<usize>()) {
+                Some(Ok(position)) => {
+                    simulation.seek(position);
+                    Ok(true)
+                }
+                _ => Err("Usage: goto N".to_string()),
+            },
+            Some(command @ ("c" | "continue" | "r" | "reverse")) => {
+                let forwards = command.starts_with('c');
+                if let Some(index) = simulation.run(&breakpoints, forwards) {
+                    writeln!(output, "Breakpoint {}: {:?}", index + 1, breakpoints[index])?;
+                }
+                Ok(true)
+            }
+            Some("b" | "break") => parse_breakpoint(&words[1..]).map(|x| {
+                breakpoints.push(x);
+                false
+            }),
+            Some("d" | "delete") => {
+                breakpoints.clear();
+                Ok(false)
+            }
+            Some("s" | "show") => Ok(true),
+            Some("w" | "draw") => {
+                write!(output, "{}", render_stacks(simulation.stacks()))?;
+                Ok(false)
+            }
+            Some("h" | "help") => {
+                writeln!(output, "{}", HELP)?;
+                Ok(false)
+            }
+            Some("q" | "quit") => break,
+            Some(other) => Err(format!("Unknown command {:?}, try help", other)),
+        };
+        match result {
+            Ok(true) => show(simulation, &mut output)?,
+            Ok(false) => {}
+            Err(message) => writeln!(output, "{}", message)?,
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_input, CrateMover9000, CrateMover9001};
+    use super::*;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn day05_simulation_undo() {
+        let initial = parse_input(EXAMPLE).unwrap();
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let mut simulation = Simulation::new(initial.clone(), crane);
+            let mut states = vec![simulation.state()];
+            while simulation.step() {
+                states.push(simulation.state());
+            }
+            assert_eq!(4, simulation.position());
+            assert!(!simulation.step());
+            for expected in states.iter().rev().skip(1) {
+                assert!(simulation.undo());
+                assert_eq!(*expected, simulation.state());
+            }
+            assert!(!simulation.undo());
+            assert_eq!(initial, simulation.state());
+            simulation.seek(3);
+            assert_eq!(states[3], simulation.state());
+            simulation.seek(1);
+            assert_eq!(states[1], simulation.state());
+            simulation.seek(100);
+            assert_eq!(states[4], simulation.state());
+        }
+    }
+
+    #[test]
+    fn day05_simulation_breakpoints() {
+        let initial = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(initial, &CrateMover9000);
+        let breakpoints = vec![
+            Breakpoint::Empty(2),
+            Breakpoint::Empty(1),
+            Breakpoint::Top(3, "D".to_string()),
+        ];
+        assert_eq!(Some(1), simulation.run(&breakpoints, true));
+        assert_eq!(2, simulation.position());
+        assert_eq!(Some(0), simulation.run(&breakpoints, true));
+        assert_eq!(3, simulation.position());
+        assert_eq!(None, simulation.run(&breakpoints, true));
+        assert_eq!(4, simulation.position());
+        assert_eq!(Some(0), simulation.run(&breakpoints, false));
+        assert_eq!(3, simulation.position());
+        assert_eq!(None, simulation.run(&[Breakpoint::Move(7)], false));
+        assert_eq!(0, simulation.position());
+        assert_eq!(Some(0), simulation.run(&[Breakpoint::Move(3)], true));
+        assert_eq!(3, simulation.position());
+    }
+
+    #[test]
+    fn day05_debugger_session() {
+        let initial = parse_input(EXAMPLE).unwrap();
+        let mut simulation = Simulation::new(initial, &CrateMover9000);
+        let input =
+            "next\nbreak empty 2\nbreak bogus\ncontinue\nprev 2\ngoto 4\ndraw\nfly\nquit\nnext\n";
+        let mut output = Vec::new();
+        debug(&mut simulation, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = "\
+At move 0/4, next: move 1 from 2 to 1
+  1: [Z] [N]
+  2: [M] [C] [D]
+  3: [P]
+> At move 1/4, next: move 3 from 1 to 3
+  1: [Z] [N] [D]
+  2: [M] [C]
+  3: [P]
+> > Usage: break empty STACK | break move N | break top STACK CRATE
+> Breakpoint 1: Empty(2)
+At move 3/4, next: move 1 from 1 to 2
+  1: [C] [M]
+  2:
+  3: [P] [D] [N] [Z]
+> At move 1/4, next: move 3 from 1 to 3
+  1: [Z] [N] [D]
+  2: [M] [C]
+  3: [P]
+> At move 4/4, finished
+  1: [C]
+  2: [M]
+  3: [P] [D] [N] [Z]
+>         [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3 
+> Unknown command \"fly\", try help
+>";
+        assert_eq!(expected, output.trim_end());
+        assert_eq!(4, simulation.position());
+    }
+}
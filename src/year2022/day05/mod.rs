@@ -0,0 +1,295 @@
+//! Supply stacks: the crate drawing, move list and crane simulation.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::solution::Solution;
+
+mod anim;
+mod cli;
+mod crane;
+mod debug;
+mod parse;
+mod render;
+mod solve;
+mod treap;
+mod validate;
+
+pub use anim::{dump_frames, play, Image, Layout};
+pub use cli::cli;
+pub use crane::{apply_moves, crane_from_name, Capacity, Crane, CrateMover9000, CrateMover9001};
+pub use debug::{debug, Breakpoint, Simulation};
+pub use parse::{parse_input, parse_input_lines, TAB_WIDTH};
+pub use render::render_stacks;
+pub use solve::solve;
+pub use treap::{apply_moves_fast, TreapStacks};
+pub use validate::{repair, validate, Repair};
+
+pub type Crate = String;
+
+/// Stacks by label, each listed bottom to top.
+pub type Stacks = HashMap<u32, VecDeque<Crate>>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Movement {
+    pub count: u32,
+    pub source: u32,
+    pub target: u32,
+}
+
+impl Movement {
+    pub fn new(count: u32, source: u32, target: u32) -> Movement {
+        Movement {
+            count,
+            source,
+            target,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub stacks: Stacks,
+    pub moves: VecDeque<Movement>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::new()
+    }
+}
+
+impl State {
+    pub fn new() -> State {
+        State {
+            stacks: HashMap::new(),
+            moves: VecDeque::new(),
+        }
+    }
+
+    /// Top crate of every non-empty stack, in label order.
+    pub fn top_items(&self) -> Vec<Crate> {
+        let mut keys: Vec<u32> = self.stacks.keys().copied().collect();
+        keys.sort();
+        let mut tops = Vec::new();
+        for key in keys {
+            if let Some(top) = self.stacks.get(&key).unwrap().back() {
+                tops.push(top.clone());
+            }
+        }
+        tops
+    }
+}
+
+/// Parses, validates and runs the moves with `apply` under the named crane,
+/// returning the top crates.
+fn top_after(
+    text: &str,
+    crane: &str,
+    apply: fn(State, &dyn Crane) -> State,
+) -> Result<String, String> {
+    let (state, lines) = parse_input_lines(text)?;
+    validate(&state, &lines)?;
+    let crane = crane_from_name(crane)?;
+    Ok(apply(state, crane.as_ref()).top_items().concat())
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 5, 'a', "vecdeque", |text| {
+            top_after(text, "9000", apply_moves)
+        }),
+        Solution::new(2022, 5, 'a', "treap", |text| {
+            top_after(text, "9000", apply_moves_fast)
+        }),
+        // Lifting three crates at a time still reverses each whole move on
+        // the CrateMover 9000. A capped 9001 answers a different puzzle.
+        Solution::new(2022, 5, 'a', "capped", |text| {
+            top_after(text, "9000:3", apply_moves)
+        }),
+        Solution::new(2022, 5, 'b', "vecdeque", |text| {
+            top_after(text, "9001", apply_moves)
+        }),
+        Solution::new(2022, 5, 'b', "treap", |text| {
+            top_after(text, "9001", apply_moves_fast)
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop::Shrink;
+    use crate::{generate, prop};
+
+    impl Shrink for State {
+        /// Drops moves, lowers move counts and removes top crates, keeping only
+        /// states that still pass `validate`.
+        fn shrink(&self) -> Vec<Self> {
+            let moves: Vec<Movement> = self.moves.iter().cloned().collect();
+            let counts: Vec<u32> = moves.iter().map(|x| x.count).collect();
+            let mut candidates = Vec::new();
+            for counts in counts.shrink() {
+                if counts.len() != moves.len() {
+                    continue;
+                }
+                let mut candidate = self.clone();
+                for (movement, count) in candidate.moves.iter_mut().zip(counts) {
+                    movement.count = count;
+                }
+                candidates.push(candidate);
+            }
+            let mut labels: Vec<u32> = self.stacks.keys().copied().collect();
+            labels.sort();
+            for label in labels {
+                if !self.stacks[&label].is_empty() {
+                    let mut candidate = self.clone();
+                    candidate.stacks.get_mut(&label).unwrap().pop_back();
+                    candidates.push(candidate);
+                }
+            }
+            let mut shorter: Vec<State> = Vec::new();
+            for size in (1..=moves.len()).rev() {
+                shorter.push(State {
+                    stacks: self.stacks.clone(),
+                    moves: moves[..moves.len() - size].iter().cloned().collect(),
+                });
+            }
+            for i in 0..moves.len() {
+                let mut candidate = self.clone();
+                candidate.moves.remove(i);
+                shorter.push(candidate);
+            }
+            shorter.extend(candidates);
+            shorter.retain(|x| validate(x, &[]).is_ok());
+            shorter
+        }
+    }
+
+    const EXAMPLE: &str = "
+    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+        ";
+
+    fn stacks(items: &[(u32, &[&str])]) -> Stacks {
+        items
+            .iter()
+            .map(|(stack, crates)| (*stack, crates.iter().map(|x| x.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn day05_example1_parse() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let expected = stacks(&[(1, &["Z", "N"]), (2, &["M", "C", "D"]), (3, &["P"])]);
+        assert_eq!(expected, state.stacks);
+        let expected_moves: VecDeque<Movement> = VecDeque::from([
+            Movement::new(1, 2, 1),
+            Movement::new(3, 1, 3),
+            Movement::new(2, 2, 1),
+            Movement::new(1, 1, 2),
+        ]);
+        assert_eq!(expected_moves, state.moves);
+    }
+
+    #[test]
+    fn day05a_example1() {
+        let final_state = apply_moves(parse_input(EXAMPLE).unwrap(), &CrateMover9000);
+        let expected = stacks(&[(1, &["C"]), (2, &["M"]), (3, &["P", "D", "N", "Z"])]);
+        assert_eq!(expected, final_state.stacks);
+        assert_eq!(0, final_state.moves.len());
+        assert_eq!(vec!["C", "M", "Z"], final_state.top_items());
+        assert_eq!(
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 \n",
+            render_stacks(&final_state.stacks)
+        );
+    }
+
+    #[test]
+    fn day05b_example1() {
+        let final_state = apply_moves(parse_input(EXAMPLE).unwrap(), &CrateMover9001);
+        let expected = stacks(&[(1, &["M"]), (2, &["C"]), (3, &["P", "Z", "N", "D"])]);
+        assert_eq!(expected, final_state.stacks);
+        assert_eq!(0, final_state.moves.len());
+        assert_eq!(vec!["M", "C", "D"], final_state.top_items());
+        assert_eq!(
+            "        [D]\n        [N]\n        [Z]\n[M] [C] [P]\n 1   2   3 \n",
+            render_stacks(&final_state.stacks)
+        );
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "CMZ" } else { "MCD" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    fn sorted_stacks(stacks: &Stacks) -> Vec<Vec<Crate>> {
+        let mut labels: Vec<&u32> = stacks.keys().collect();
+        labels.sort();
+        labels
+            .into_iter()
+            .map(|x| stacks[x].iter().cloned().collect())
+            .collect()
+    }
+
+    /// Moves one crate at a time, or lifts each batch and puts it back in order
+    /// when `in_order` is set as the CrateMover 9001 does.
+    fn naive_apply(state: &State, in_order: bool) -> Vec<Vec<Crate>> {
+        let mut labels: Vec<u32> = state.stacks.keys().copied().collect();
+        labels.sort();
+        let mut stacks = sorted_stacks(&state.stacks);
+        for movement in state.moves.iter() {
+            let source = labels.iter().position(|x| *x == movement.source).unwrap();
+            let target = labels.iter().position(|x| *x == movement.target).unwrap();
+            let mut lifted = Vec::new();
+            for _ in 0..movement.count {
+                let item = stacks[source].pop().unwrap();
+                if in_order {
+                    lifted.push(item);
+                } else {
+                    stacks[target].push(item);
+                }
+            }
+            while let Some(item) = lifted.pop() {
+                stacks[target].push(item);
+            }
+        }
+        stacks
+    }
+
+    fn check_crane(crane: &dyn Crane, in_order: bool) {
+        prop::check(
+            5,
+            2000,
+            |rng| {
+                let stacks = 1 + rng.below(5) as u32;
+                let crates = rng.below(20) as usize;
+                let moves = rng.below(30) as usize;
+                generate::crate_puzzle(rng, stacks, crates, moves, 6)
+            },
+            |state| {
+                let expected = naive_apply(state, in_order);
+                let parsed = parse_input(&state.to_string())?;
+                let slow = apply_moves(parsed.clone(), crane);
+                prop::expect_eq(&expected, &sorted_stacks(&slow.stacks))?;
+                let fast = apply_moves_fast(parsed, crane);
+                prop::expect_eq(&expected, &sorted_stacks(&fast.stacks))
+            },
+        );
+    }
+
+    #[test]
+    fn day05a_matches_naive() {
+        check_crane(&CrateMover9000, false);
+    }
+
+    #[test]
+    fn day05b_matches_naive() {
+        check_crane(&CrateMover9001, true);
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
+use super::{Movement, State};
+
+/// Columns a tab advances to in drawings, matching the 4-wide crate columns.
+pub const TAB_WIDTH: usize = 4;
+
+impl FromStr for Movement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_movement(s).map_err(|(column, message)| format!("column {}: {}", column, message))
+    }
+}
+
+/// Token of a drawing line with its 0-based column span `[start, end)`.
+#[derive(Debug)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for ch in line.chars() {
+        if ch == '\t' {
+            let width = TAB_WIDTH - column % TAB_WIDTH;
+            expanded.extend(std::iter::repeat_n(' ', width));
+            column += width;
+        } else {
+            expanded.push(ch);
+            column += 1;
+        }
+    }
+    expanded
+}
+
+fn char_column(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count()
+}
+
+fn label_tokens(line: &str) -> Result<Vec<Token<'_>>, (usize, String)> {
+    let mut tokens = Vec::new();
+    let mut rest = line.char_indices().peekable();
+    while let Some((index, ch)) = rest.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        let mut end = index + ch.len_utf8();
+        while let Some((next, ch)) = rest.peek() {
+            if ch.is_whitespace() {
+                break;
+            }
+            end = next + ch.len_utf8();
+            rest.next();
+        }
+        let text = &line[index..end];
+        if !text.chars().all(|x| x.is_ascii_digit()) {
+            let column = char_column(line, index);
+            return Err((column, format!("expected a stack label, found {:?}", text)));
+        }
+        tokens.push(Token {
+            text,
+            start: char_column(line, index),
+            end: char_column(line, end),
+        });
+    }
+    Ok(tokens)
+}
+
+fn crate_tokens(line: &str) -> Result<Vec<Token<'_>>, (usize, String)> {
+    let mut tokens = Vec::new();
+    let mut rest = line.char_indices();
+    while let Some((index, ch)) = rest.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        if ch != '[' {
+            let column = char_column(line, index);
+            return Err((column, format!("expected '[', found {:?}", ch)));
+        }
+        let close = match rest.by_ref().find(|(_, ch)| *ch == ']') {
+            Some((close, _)) => close,
+            None => {
+                let column = char_column(line, index);
+                return Err((column, "crate is missing its closing ']'".to_string()));
+            }
+        };
+        let text = &line[index + 1..close];
+        if text.trim().is_empty() || text.contains('[') {
+            let column = char_column(line, index);
+            return Err((column, format!("bad crate label {:?}", text)));
+        }
+        tokens.push(Token {
+            text,
+            start: char_column(line, index),
+            end: char_column(line, close) + 1,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Parses `move N from A to B`, returning the 1-based column of any problem.
+fn parse_movement(line: &str) -> Result<Movement, (usize, String)> {
+    let mut values = Vec::new();
+    let mut words = line.split(' ').scan(0, |offset, word| {
+        let column = *offset;
+        *offset += word.chars().count() + 1;
+        Some((column + 1, word))
+    });
+    for keyword in ["move", "from", "to"] {
+        match words.next() {
+            Some((_, word)) if word == keyword => {}
+            Some((column, word)) => {
+                return Err((column, format!("expected {:?}, found {:?}", keyword, word)));
+            }
+            None => return Err((line.chars().count() + 1, format!("missing {:?}", keyword))),
+        }
+        match words.next() {
+            Some((column, word)) => match word.parse::<u32>() {
+                Ok(value) => values.push(value),
+                Err(_) => return Err((column, format!("expected a number, found {:?}", word))),
+            },
+            None => return Err((line.chars().count() + 1, "missing a number".to_string())),
+        }
+    }
+    if let Some((column, word)) = words.next() {
+        return Err((column, format!("unexpected {:?}", word)));
+    }
+    Ok(Movement::new(values[0], values[1], values[2]))
+}
+
+fn located(line: usize, (column, message): (usize, String)) -> String {
+    format!("line {}, column {}: {}", line, column, message)
+}
+
+/// Parses the crate drawing and move list.
+///
+/// Stack labels may have several digits and crates several characters; rows
+/// may be shorter than the label row (stripped trailing whitespace), use CRLF
+/// endings or tabs. A blank label row with no drawing above it stands for no
+/// stacks at all, the way `render_stacks` draws them. Errors carry 1-based
+/// line and column numbers, columns counted after tab expansion.
+pub fn parse_input(text: &str) -> Result<State, String> {
+    parse_input_lines(text).map(|(state, _)| state)
+}
+
+/// Same as `parse_input`, also returning the 1-based line number of each move.
+pub fn parse_input_lines(text: &str) -> Result<(State, Vec<usize>), String> {
+    let mut state = State::new();
+    let mut lines = Vec::new();
+    let mut drawing: Vec<(usize, String)> = Vec::new();
+    let mut blank_label_row = false;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = expand_tabs(line.trim_end_matches('\r'));
+        if line.trim().is_empty() {
+            blank_label_row |= state.moves.is_empty();
+            continue;
+        }
+        if line.trim_start().starts_with("move") {
+            let indent = line.len() - line.trim_start().len();
+            let movement = parse_movement(line.trim())
+                .map_err(|(column, message)| located(number, (column + indent, message)))?;
+            state.moves.push_back(movement);
+            lines.push(number);
+        } else if state.moves.is_empty() {
+            drawing.push((number, line));
+        } else {
+            return Err(located(number, (1, "expected a move".to_string())));
+        }
+    }
+
+    let (label_line, labels) = match drawing.pop() {
+        Some(labels) => labels,
+        None if blank_label_row => return Ok((state, lines)),
+        None => return Err("line 1, column 1: missing crate drawing".to_string()),
+    };
+    let labels = label_tokens(&labels).map_err(|x| located(label_line, (x.0 + 1, x.1)))?;
+    let mut columns: Vec<(u32, usize, usize)> = Vec::new();
+    for label in labels {
+        let stack = label.text.parse().map_err(|_| {
+            located(
+                label_line,
+                (label.start + 1, "stack label too large".to_string()),
+            )
+        })?;
+        if columns.iter().any(|x| x.0 == stack) {
+            let message = format!("duplicate stack label {}", stack);
+            return Err(located(label_line, (label.start + 1, message)));
+        }
+        columns.push((stack, label.start, label.end));
+        state.stacks.insert(stack, VecDeque::new());
+    }
+
+    let mut gaps: HashMap<u32, usize> = HashMap::new();
+    for (number, line) in drawing.iter().rev() {
+        let tokens = crate_tokens(line).map_err(|x| located(*number, (x.0 + 1, x.1)))?;
+        let mut filled = Vec::new();
+        for token in tokens {
+            let under: Vec<u32> = columns
+                .iter()
+                .filter(|(_, start, end)| *start < token.end && token.start < *end)
+                .map(|x| x.0)
+                .collect();
+            let stack = match under[..] {
+                [stack] => stack,
+                [] => {
+                    let message = format!("crate [{}] is not above a stack label", token.text);
+                    return Err(located(*number, (token.start + 1, message)));
+                }
+                _ => {
+                    let message = format!("crate [{}] is above several stack labels", token.text);
+                    return Err(located(*number, (token.start + 1, message)));
+                }
+            };
+            if filled.contains(&stack) {
+                let message = format!("crate [{}] shares a row with another crate", token.text);
+                return Err(located(*number, (token.start + 1, message)));
+            }
+            if let Some(gap) = gaps.get(&stack) {
+                let message = format!(
+                    "crate [{}] floats above the empty slot on line {}",
+                    token.text, gap
+                );
+                return Err(located(*number, (token.start + 1, message)));
+            }
+            filled.push(stack);
+            state
+                .stacks
+                .get_mut(&stack)
+                .unwrap()
+                .push_back(token.text.to_string());
+        }
+        for (stack, _, _) in columns.iter() {
+            if !filled.contains(stack) {
+                gaps.entry(*stack).or_insert(*number);
+            }
+        }
+    }
+
+    Ok((state, lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Stacks;
+    use super::*;
+
+    fn stacks(items: &[(u32, &[&str])]) -> Stacks {
+        items
+            .iter()
+            .map(|(stack, crates)| (*stack, crates.iter().map(|x| x.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn day05_parse_example1() {
+        let input = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+        ";
+        let state = parse_input(input).unwrap();
+        let expected = stacks(&[(1, &["Z", "N"]), (2, &["M", "C", "D"]), (3, &["P"])]);
+        assert_eq!(expected, state.stacks);
+        let expected_moves: VecDeque<Movement> = vec![
+            Movement::new(1, 2, 1),
+            Movement::new(3, 1, 3),
+            Movement::new(2, 2, 1),
+            Movement::new(1, 1, 2),
+        ]
+        .into();
+        assert_eq!(expected_moves, state.moves);
+        assert_eq!(vec!["N", "D", "P"], state.top_items());
+
+        let stripped: String = input
+            .lines()
+            .map(|x| x.trim_end().to_string() + "\r\n")
+            .collect();
+        assert_eq!(state, parse_input(&stripped).unwrap());
+        let tabbed = input.replace("    [D]", "\t[D]");
+        assert_eq!(state, parse_input(&tabbed).unwrap());
+    }
+
+    #[test]
+    fn day05_parse_wide_drawing() {
+        let input = "
+                                    [X]
+[A]                         [Q]     [Y]
+[B] [C] [D] [E] [F] [G] [H] [I] [J] [K] [L]
+ 1   2   3   4   5   6   7   8   9  10  11
+
+move 2 from 10 to 11
+";
+        let state = parse_input(input).unwrap();
+        assert_eq!(11, state.stacks.len());
+        assert_eq!(stacks(&[(10, &["K", "Y", "X"])])[&10], state.stacks[&10]);
+        assert_eq!(vec!["L".to_string()], Vec::from(state.stacks[&11].clone()));
+        assert_eq!(Movement::new(2, 10, 11), state.moves[0]);
+        assert_eq!(vec![7], parse_input_lines(input).unwrap().1);
+
+        let input = "
+[AB]
+[C]   [DEF]
+  1     2
+";
+        let state = parse_input(input).unwrap();
+        assert_eq!(stacks(&[(1, &["C", "AB"]), (2, &["DEF"])]), state.stacks);
+        assert_eq!(vec!["AB", "DEF"], state.top_items());
+
+        let input = "[A]\n 1   2\n";
+        let state = parse_input(input).unwrap();
+        assert!(state.stacks[&2].is_empty());
+        assert_eq!(vec!["A"], state.top_items());
+    }
+
+    #[test]
+    fn day05_parse_errors() {
+        let cases = [
+            (
+                "[A] [B]\n 1\n",
+                "line 1, column 5: crate [B] is not above a stack label",
+            ),
+            (
+                "[A] [B\n 1   2\n",
+                "line 1, column 5: crate is missing its closing ']'",
+            ),
+            (
+                "[A] B\n 1   2\n",
+                "line 1, column 5: expected '[', found 'B'",
+            ),
+            ("[A] []\n 1   2\n", "line 1, column 5: bad crate label \"\""),
+            (
+                "[A]\n 1   x\n",
+                "line 2, column 6: expected a stack label, found \"x\"",
+            ),
+            ("[A]\n 1   1\n", "line 2, column 6: duplicate stack label 1"),
+            (
+                "[A]\n    [C]\n[B] [D]\n 1   2\n",
+                "line 1, column 1: crate [A] floats above the empty slot on line 2",
+            ),
+            (
+                "[A]\n 1\nmove 1 from 1 to\n",
+                "line 3, column 17: missing a number",
+            ),
+            (
+                "[A]\n 1\nmove 1 frm 1 to 2\n",
+                "line 3, column 8: expected \"from\", found \"frm\"",
+            ),
+            (
+                "[A]\n 1\nmove x from 1 to 2\n",
+                "line 3, column 6: expected a number, found \"x\"",
+            ),
+            (
+                "[A]\n 1\nmove 1 from 1 to 2\n[B]\n",
+                "line 4, column 1: expected a move",
+            ),
+            ("", "line 1, column 1: missing crate drawing"),
+            (
+                "move 1 from 1 to 2\n",
+                "line 1, column 1: missing crate drawing",
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(Err(error.to_string()), parse_input(input), "{:?}", input);
+        }
+        assert_eq!(Ok(State::new()), parse_input("\n"));
+        let mut moved = State::new();
+        moved.moves.push_back(Movement::new(1, 1, 2));
+        assert_eq!(Ok(moved), parse_input("\n\nmove 1 from 1 to 2\n"));
+        assert_eq!(Ok(Movement::new(1, 2, 3)), "move 1 from 2 to 3".parse());
+        assert_eq!(
+            Err("column 20: unexpected \"now\"".to_string()),
+            "move 1 from 2 to 3 now".parse::<Movement>()
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use super::{Movement, Stacks, State};
+
+/// Offset that centers `length` columns within `width`.
+fn centered(width: usize, length: usize) -> usize {
+    (width - length).div_ceil(2)
+}
+
+/// Draws the stacks in the puzzle's format: one `[X]` row per height with the
+/// label row last, stacks in label order and every line padded to full width.
+///
+/// Columns widen to fit long crates or labels, and crates and labels are both
+/// centered so `parse_input` puts every crate back on its stack. Without any
+/// stacks the label row is left blank.
+pub fn render_stacks(stacks: &Stacks) -> String {
+    let mut keys: Vec<u32> = stacks.keys().copied().collect();
+    keys.sort();
+    let widths: Vec<usize> = keys
+        .iter()
+        .map(|key| {
+            let widest = stacks[key].iter().map(|x| x.chars().count() + 2).max();
+            let label = key.to_string().len();
+            widest.unwrap_or(3).max(label).max(3)
+        })
+        .collect();
+    let height = stacks.values().map(|x| x.len()).max().unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for row in (0..height).rev() {
+        let cells: Vec<String> = keys
+            .iter()
+            .zip(widths.iter())
+            .map(|(key, width)| match stacks[key].get(row) {
+                Some(item) => {
+                    let cell = format!("[{}]", item);
+                    let offset = centered(*width, cell.chars().count());
+                    format!("{:offset$}{:<rest$}", "", cell, rest = width - offset)
+                }
+                None => " ".repeat(*width),
+            })
+            .collect();
+        lines.push(cells.join(" "));
+    }
+    let labels: Vec<String> = keys
+        .iter()
+        .zip(widths.iter())
+        .map(|(key, width)| {
+            let label = key.to_string();
+            let offset = centered(*width, label.len());
+            format!("{:offset$}{:<rest$}", "", label, rest = width - offset)
+        })
+        .collect();
+    lines.push(labels.join(" "));
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count, self.source, self.target
+        )
+    }
+}
+
+impl fmt::Display for State {
+    /// Writes the state as puzzle input: the drawing, a blank line, then the
+    /// remaining moves.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", render_stacks(&self.stacks))?;
+        if !self.moves.is_empty() {
+            writeln!(f)?;
+            for movement in self.moves.iter() {
+                writeln!(f, "{}", movement)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse_input;
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn day05_render_example1() {
+        let input = concat!(
+            "    [D]    \n",
+            "[N] [C]    \n",
+            "[Z] [M] [P]\n",
+            " 1   2   3 \n",
+            "\n",
+            "move 1 from 2 to 1\n",
+            "move 3 from 1 to 3\n",
+            "move 2 from 2 to 1\n",
+            "move 1 from 1 to 2\n",
+        );
+        let state = parse_input(input).unwrap();
+        assert_eq!(input, state.to_string());
+        assert_eq!("move 3 from 1 to 3", state.moves[1].to_string());
+        let mut stacks = state.stacks.clone();
+        stacks.get_mut(&2).unwrap().clear();
+        assert_eq!(
+            "[N]        \n[Z]     [P]\n 1   2   3 \n",
+            render_stacks(&stacks)
+        );
+        assert_eq!("\n", render_stacks(&Stacks::new()));
+        assert_eq!(Ok(State::new()), parse_input(&State::new().to_string()));
+    }
+
+    #[test]
+    fn day05_render_wide() {
+        let input = concat!(
+            "                                    [X]        \n",
+            "[A]                         [Q]     [Y]        \n",
+            "[B] [C] [D] [E] [F] [G] [H] [I] [J] [K] [L]    \n",
+            " 1   2   3   4   5   6   7   8   9   10  11 100\n",
+        );
+        let state = parse_input(input).unwrap();
+        assert_eq!(input, state.to_string());
+
+        let input = "[AB]\n[C]   [DEF]\n  1     2\n";
+        let state = parse_input(input).unwrap();
+        let rendered = state.to_string();
+        assert_eq!("[AB]      \n [C] [DEF]\n  1    2  \n", rendered);
+        assert_eq!(state, parse_input(&rendered).unwrap());
+    }
+
+    #[test]
+    fn day05_render_round_trip() {
+        let mut rng = Rng::new(36);
+        for _ in 0..200 {
+            let mut state = State::new();
+            for _ in 0..rng.below(12) {
+                let label = 1 + rng.below(120) as u32;
+                let stack = state.stacks.entry(label).or_default();
+                for _ in 0..rng.below(6) {
+                    let length = 1 + rng.below(3) as usize;
+                    let name: String = (0..length)
+                        .map(|_| (b'A' + rng.below(26) as u8) as char)
+                        .collect();
+                    stack.push_back(name);
+                }
+            }
+            for _ in 0..rng.below(4) {
+                let count = 1 + rng.below(5) as u32;
+                let source = 1 + rng.below(9) as u32;
+                let target = 1 + rng.below(9) as u32;
+                state.moves.push_back(Movement::new(count, source, target));
+            }
+            let rendered = state.to_string();
+            assert_eq!(Ok(state), parse_input(&rendered), "{}", rendered);
+        }
+    }
+}
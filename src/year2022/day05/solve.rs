@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use super::{Crane, Movement, Stacks};
+
+/// IDA* search state, with stacks in label order and crates as ids shared by
+/// equal names.
+struct Search {
+    labels: Vec<u32>,
+    stacks: Vec<Vec<usize>>,
+    target: Vec<Vec<usize>>,
+    reverse: bool,
+    budget: usize,
+    expanded: usize,
+    path: Vec<(usize, usize, usize)>,
+}
+
+enum Outcome {
+    Found,
+    /// Smallest estimate above the bound, if anything was cut off.
+    Exceeded(Option<usize>),
+}
+
+impl Search {
+    /// Admissible estimate: every stack whose crates differ from the target
+    /// above their common bottom must lose a crate, every stack the target
+    /// grows past that point must gain one, and each move serves at most one
+    /// of each.
+    fn estimate(&self) -> usize {
+        let mut sources = 0;
+        let mut targets = 0;
+        for (stack, target) in self.stacks.iter().zip(self.target.iter()) {
+            let common = stack
+                .iter()
+                .zip(target.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            if stack.len() > common {
+                sources += 1;
+            }
+            if target.len() > common {
+                targets += 1;
+            }
+        }
+        sources.max(targets)
+    }
+
+    fn lift(&mut self, count: usize, source: usize, target: usize) {
+        let start = self.stacks[source].len() - count;
+        let mut block = self.stacks[source].split_off(start);
+        if self.reverse {
+            block.reverse();
+        }
+        self.stacks[target].extend(block);
+    }
+
+    fn search(&mut self, depth: usize, bound: usize) -> Result<Outcome, String> {
+        let estimate = self.estimate();
+        if estimate == 0 {
+            return Ok(Outcome::Found);
+        }
+        if depth + estimate > bound {
+            return Ok(Outcome::Exceeded(Some(depth + estimate)));
+        }
+        self.expanded += 1;
+        if self.expanded > self.budget {
+            return Err(format!(
+                "No move sequence found within {} nodes",
+                self.budget
+            ));
+        }
+        let mut next = None;
+        for source in 0..self.stacks.len() {
+            for target in 0..self.stacks.len() {
+                if source == target {
+                    continue;
+                }
+                for count in 1..=self.stacks[source].len() {
+                    // Moving the same block straight back only undoes a move.
+                    if self.path.last() == Some(&(count, target, source)) {
+                        continue;
+                    }
+                    self.lift(count, source, target);
+                    self.path.push((count, source, target));
+                    match self.search(depth + 1, bound)? {
+                        Outcome::Found => return Ok(Outcome::Found),
+                        Outcome::Exceeded(cost) => {
+                            next = match (next, cost) {
+                                (Some(a), Some(b)) => Some(usize::min(a, b)),
+                                (a, b) => a.or(b),
+                            };
+                        }
+                    }
+                    self.path.pop();
+                    self.lift(count, target, source);
+                }
+            }
+        }
+        Ok(Outcome::Exceeded(next))
+    }
+}
+
+/// Finds a shortest list of moves turning `initial` into `target` with the
+/// given crane, expanding at most `budget` search nodes.
+///
+/// Both drawings need the same stack labels and the same crates, and the
+/// crane must move each block at once (see `Crane::reverses`).
+pub fn solve(
+    initial: &Stacks,
+    target: &Stacks,
+    crane: &dyn Crane,
+    budget: usize,
+) -> Result<Vec<Movement>, String> {
+    let reverse = match crane.reverses() {
+        Some(reverse) => reverse,
+        None => return Err(format!("Cannot search moves for {}", crane.name())),
+    };
+    let mut labels: Vec<u32> = initial.keys().copied().collect();
+    labels.sort();
+    let mut target_labels: Vec<u32> = target.keys().copied().collect();
+    target_labels.sort();
+    if labels != target_labels {
+        return Err(format!(
+            "Stack labels differ: {:?} and {:?}",
+            labels, target_labels
+        ));
+    }
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut intern = |stacks: &Stacks, label: &u32| -> Vec<usize> {
+        stacks[label]
+            .iter()
+            .map(|x| {
+                let next = ids.len();
+                *ids.entry(x.clone()).or_insert(next)
+            })
+            .collect()
+    };
+    let stacks: Vec<Vec<usize>> = labels.iter().map(|x| intern(initial, x)).collect();
+    let goal: Vec<Vec<usize>> = labels.iter().map(|x| intern(target, x)).collect();
+    let mut have: Vec<usize> = stacks.iter().flatten().copied().collect();
+    let mut want: Vec<usize> = goal.iter().flatten().copied().collect();
+    have.sort();
+    want.sort();
+    if have != want {
+        return Err("The drawings hold different crates".to_string());
+    }
+
+    let mut search = Search {
+        labels,
+        stacks,
+        target: goal,
+        reverse,
+        budget,
+        expanded: 0,
+        path: Vec::new(),
+    };
+    let mut bound = search.estimate();
+    loop {
+        match search.search(0, bound)? {
+            Outcome::Found => break,
+            Outcome::Exceeded(Some(next)) => bound = next,
+            Outcome::Exceeded(None) => return Err("No move sequence exists".to_string()),
+        }
+    }
+    Ok(search
+        .path
+        .iter()
+        .map(|(count, source, target)| {
+            let source = search.labels[*source];
+            let target = search.labels[*target];
+            Movement::new(*count as u32, source, target)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use super::super::{apply_moves, parse_input, CrateMover9000, CrateMover9001, State};
+    use super::*;
+    use crate::rng::Rng;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    fn run(initial: &Stacks, moves: Vec<Movement>, crane: &dyn Crane) -> Stacks {
+        let state = State {
+            stacks: initial.clone(),
+            moves: moves.into(),
+        };
+        apply_moves(state, crane).stacks
+    }
+
+    /// Length of a shortest move list, by breadth-first search.
+    fn shortest(initial: &Stacks, target: &Stacks, crane: &dyn Crane) -> usize {
+        let mut labels: Vec<u32> = initial.keys().copied().collect();
+        labels.sort();
+        let key = |stacks: &Stacks| -> Vec<VecDeque<String>> {
+            labels.iter().map(|x| stacks[x].clone()).collect()
+        };
+        let mut seen = HashSet::from([key(initial)]);
+        let mut queue = VecDeque::from([(initial.clone(), 0)]);
+        while let Some((stacks, depth)) = queue.pop_front() {
+            if key(&stacks) == key(target) {
+                return depth;
+            }
+            for source in labels.iter() {
+                for target in labels.iter().filter(|x| *x != source) {
+                    for count in 1..=stacks[source].len() {
+                        let movement = Movement::new(count as u32, *source, *target);
+                        let next = run(&stacks, vec![movement], crane);
+                        if seen.insert(key(&next)) {
+                            queue.push_back((next, depth + 1));
+                        }
+                    }
+                }
+            }
+        }
+        panic!("Unreachable target");
+    }
+
+    #[test]
+    fn day05_solve_example() {
+        let state = parse_input(EXAMPLE).unwrap();
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let target = apply_moves(state.clone(), crane).stacks;
+            let moves = solve(&state.stacks, &target, crane, 1_000_000).unwrap();
+            assert_eq!(shortest(&state.stacks, &target, crane), moves.len());
+            assert_eq!(target, run(&state.stacks, moves, crane));
+        }
+        let moves = solve(&state.stacks, &state.stacks, &CrateMover9000, 0).unwrap();
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn day05_solve_matches_bfs() {
+        let mut rng = Rng::new(40);
+        for _ in 0..30 {
+            let mut stacks = Stacks::new();
+            for label in 1..=3 {
+                stacks.insert(label, VecDeque::new());
+            }
+            for name in ["A", "B", "C", "D", "A"] {
+                let label = 1 + rng.below(3) as u32;
+                stacks.get_mut(&label).unwrap().push_back(name.to_string());
+            }
+            let mut target = stacks.clone();
+            for _ in 0..rng.below(5) {
+                let source = 1 + rng.below(3) as u32;
+                let height = target[&source].len() as u64;
+                if height > 0 {
+                    let count = 1 + rng.below(height) as u32;
+                    let movement = Movement::new(count, source, 1 + (source % 3));
+                    target = run(&target, vec![movement], &CrateMover9000);
+                }
+            }
+            for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+                let moves = solve(&stacks, &target, crane, 1_000_000).unwrap();
+                assert_eq!(shortest(&stacks, &target, crane), moves.len());
+                assert_eq!(target, run(&stacks, moves, crane));
+            }
+        }
+    }
+
+    #[test]
+    fn day05_solve_errors() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut target = state.stacks.clone();
+        target.get_mut(&3).unwrap().push_back("Q".to_string());
+        assert_eq!(
+            Err("The drawings hold different crates".to_string()),
+            solve(&state.stacks, &target, &CrateMover9000, 1000)
+        );
+        target.remove(&3);
+        assert!(solve(&state.stacks, &target, &CrateMover9000, 1000).is_err());
+
+        let target = apply_moves(state.clone(), &CrateMover9000).stacks;
+        assert_eq!(
+            Err("No move sequence found within 3 nodes".to_string()),
+            solve(&state.stacks, &target, &CrateMover9000, 3)
+        );
+
+        // A single stack can never be rearranged.
+        let single = parse_input("[A]\n[B]\n 1\n").unwrap().stacks;
+        let flipped = parse_input("[B]\n[A]\n 1\n").unwrap().stacks;
+        assert_eq!(
+            Err("No move sequence exists".to_string()),
+            solve(&single, &flipped, &CrateMover9001, 1000)
+        );
+    }
+}
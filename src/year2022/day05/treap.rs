@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use super::{apply_moves, Crane, Crate, Movement, Stacks, State};
+use crate::rng::Rng;
+
+const NIL: usize = usize::MAX;
+
+/// Node of an implicit treap: ordered by position rather than by key, with
+/// a lazy flag reversing its whole subtree.
+#[derive(Debug, Clone)]
+struct Node {
+    item: usize,
+    priority: u64,
+    left: usize,
+    right: usize,
+    size: usize,
+    reversed: bool,
+}
+
+/// Stacks kept as one implicit treap each, sharing a node arena, so moving
+/// `count` crates costs `O(log n)` whatever the count: the top is split off,
+/// flagged as reversed if needed and merged onto the target.
+#[derive(Debug, Clone)]
+pub struct TreapStacks {
+    nodes: Vec<Node>,
+    names: Vec<Crate>,
+    labels: Vec<u32>,
+    roots: HashMap<u32, usize>,
+}
+
+impl TreapStacks {
+    pub fn new(stacks: Stacks, seed: u64) -> TreapStacks {
+        let mut rng = Rng::new(seed);
+        let mut treap = TreapStacks {
+            nodes: Vec::new(),
+            names: Vec::new(),
+            labels: stacks.keys().copied().collect(),
+            roots: HashMap::new(),
+        };
+        treap.labels.sort();
+        for (label, stack) in stacks {
+            let mut root = NIL;
+            for name in stack {
+                let node = treap.nodes.len();
+                treap.nodes.push(Node {
+                    item: treap.names.len(),
+                    priority: rng.next_u64(),
+                    left: NIL,
+                    right: NIL,
+                    size: 1,
+                    reversed: false,
+                });
+                treap.names.push(name);
+                root = treap.merge(root, node);
+            }
+            treap.roots.insert(label, root);
+        }
+        treap
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let size = 1 + self.size(self.nodes[node].left) + self.size(self.nodes[node].right);
+        self.nodes[node].size = size;
+    }
+
+    fn flip(&mut self, node: usize) {
+        if node != NIL {
+            self.nodes[node].reversed ^= true;
+        }
+    }
+
+    /// Applies a pending reversal to the node's children.
+    fn push_down(&mut self, node: usize) {
+        if self.nodes[node].reversed {
+            let Node { left, right, .. } = self.nodes[node];
+            self.nodes[node].left = right;
+            self.nodes[node].right = left;
+            self.nodes[node].reversed = false;
+            self.flip(left);
+            self.flip(right);
+        }
+    }
+
+    /// Splits off the first `count` positions, returning both halves.
+    fn split(&mut self, node: usize, count: usize) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+        self.push_down(node);
+        let left = self.nodes[node].left;
+        if self.size(left) < count {
+            let rest = count - self.size(left) - 1;
+            let (middle, right) = self.split(self.nodes[node].right, rest);
+            self.nodes[node].right = middle;
+            self.update(node);
+            (node, right)
+        } else {
+            let (first, middle) = self.split(left, count);
+            self.nodes[node].left = middle;
+            self.update(node);
+            (first, node)
+        }
+    }
+
+    fn merge(&mut self, lhs: usize, rhs: usize) -> usize {
+        if lhs == NIL {
+            return rhs;
+        }
+        if rhs == NIL {
+            return lhs;
+        }
+        if self.nodes[lhs].priority > self.nodes[rhs].priority {
+            self.push_down(lhs);
+            let merged = self.merge(self.nodes[lhs].right, rhs);
+            self.nodes[lhs].right = merged;
+            self.update(lhs);
+            lhs
+        } else {
+            self.push_down(rhs);
+            let merged = self.merge(lhs, self.nodes[rhs].left);
+            self.nodes[rhs].left = merged;
+            self.update(rhs);
+            rhs
+        }
+    }
+
+    fn root(&self, stack: u32) -> usize {
+        match self.roots.get(&stack) {
+            Some(root) => *root,
+            None => panic!("Unknown stack: {}", stack),
+        }
+    }
+
+    pub fn height(&self, stack: u32) -> usize {
+        self.size(self.root(stack))
+    }
+
+    /// Moves the top crates of one stack to another, reversing the block when
+    /// `reverse` is set and the stacks differ.
+    pub fn lift(&mut self, movement: &Movement, reverse: bool) {
+        let source = self.root(movement.source);
+        let target = self.root(movement.target);
+        let count = movement.count as usize;
+        let height = self.size(source);
+        if height < count {
+            panic!(
+                "Cannot move {} crates from stack {} holding {}",
+                count, movement.source, height
+            );
+        }
+        let (rest, block) = self.split(source, height - count);
+        if movement.source == movement.target {
+            let root = self.merge(rest, block);
+            self.roots.insert(movement.source, root);
+            return;
+        }
+        if reverse {
+            self.flip(block);
+        }
+        self.roots.insert(movement.source, rest);
+        let target = self.merge(target, block);
+        self.roots.insert(movement.target, target);
+    }
+
+    /// Top crate of the stack, found without rebalancing.
+    pub fn top(&self, stack: u32) -> Option<&Crate> {
+        let mut node = self.root(stack);
+        let mut reversed = false;
+        let mut top = None;
+        while node != NIL {
+            reversed ^= self.nodes[node].reversed;
+            top = Some(&self.names[self.nodes[node].item]);
+            node = if reversed {
+                self.nodes[node].left
+            } else {
+                self.nodes[node].right
+            };
+        }
+        top
+    }
+
+    /// Top crate of every non-empty stack, in label order.
+    pub fn top_items(&self) -> Vec<Crate> {
+        self.labels
+            .iter()
+            .filter_map(|x| self.top(*x).cloned())
+            .collect()
+    }
+
+    pub fn into_stacks(mut self) -> Stacks {
+        let mut stacks = Stacks::new();
+        for label in self.labels.clone() {
+            let mut stack = Vec::with_capacity(self.height(label));
+            let mut pending = Vec::new();
+            let mut node = self.root(label);
+            while node != NIL || !pending.is_empty() {
+                while node != NIL {
+                    self.push_down(node);
+                    pending.push(node);
+                    node = self.nodes[node].left;
+                }
+                let next = pending.pop().unwrap();
+                stack.push(std::mem::take(&mut self.names[self.nodes[next].item]));
+                node = self.nodes[next].right;
+            }
+            stacks.insert(label, stack.into());
+        }
+        stacks
+    }
+}
+
+/// Same result as `apply_moves`, in `O(log n)` per move for cranes that
+/// move each block at once, falling back to `apply_moves` for the others.
+pub fn apply_moves_fast(initial_state: State, crane: &dyn Crane) -> State {
+    let reverse = match crane.reverses() {
+        Some(reverse) => reverse,
+        None => return apply_moves(initial_state, crane),
+    };
+    let mut treap = TreapStacks::new(initial_state.stacks, 5);
+    for movement in initial_state.moves.iter() {
+        treap.lift(movement, reverse);
+    }
+    State {
+        stacks: treap.into_stacks(),
+        moves: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_input, Capacity, CrateMover9000, CrateMover9001};
+    use super::*;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn day05_treap_example() {
+        let state = parse_input(EXAMPLE).unwrap();
+        let mut treap = TreapStacks::new(state.stacks.clone(), 1);
+        for movement in state.moves.iter() {
+            treap.lift(movement, true);
+        }
+        assert_eq!(vec!["C", "M", "Z"], treap.top_items());
+        assert_eq!(4, treap.height(3));
+
+        let slow = apply_moves(state.clone(), &CrateMover9001);
+        let fast = apply_moves_fast(state, &CrateMover9001);
+        assert_eq!(slow, fast);
+        assert_eq!(vec!["M", "C", "D"], fast.top_items());
+    }
+
+    #[test]
+    fn day05_treap_matches_apply_moves() {
+        let mut rng = Rng::new(39);
+        for _ in 0..50 {
+            let mut state = State::new();
+            let stacks = 1 + rng.below(6) as u32;
+            let mut heights = Vec::new();
+            for label in 1..=stacks {
+                let height = rng.below(12) as usize;
+                let stack = (0..height).map(|i| format!("{}{}", label, i)).collect();
+                state.stacks.insert(label, stack);
+                heights.push(height);
+            }
+            for _ in 0..rng.below(40) {
+                let source = rng.below(stacks as u64) as usize;
+                let target = rng.below(stacks as u64) as usize;
+                let count = rng.below(heights[source] as u64 + 1) as usize;
+                heights[source] -= count;
+                heights[target] += count;
+                let movement = Movement::new(count as u32, source as u32 + 1, target as u32 + 1);
+                state.moves.push_back(movement);
+            }
+            let limited = Capacity {
+                crane: CrateMover9000,
+                limit: 2,
+            };
+            let cranes: [&dyn Crane; 3] = [&CrateMover9000, &CrateMover9001, &limited];
+            for crane in cranes {
+                let slow = apply_moves(state.clone(), crane);
+                let fast = apply_moves_fast(state.clone(), crane);
+                assert_eq!(slow, fast, "{}\n{}", crane.name(), state);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use super::{Movement, State};
+
+/// What to do with a move that cannot be carried out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    /// Move as many crates as the source holds, skipping moves that
+    /// name an unknown stack.
+    Clamp,
+    /// Drop the move entirely.
+    Skip,
+}
+
+/// Where a move came from: its input line if known, otherwise its 1-based
+/// position in the move list.
+fn location(lines: &[usize], index: usize) -> String {
+    match lines.get(index) {
+        Some(line) => format!("line {}", line),
+        None => format!("move {}", index + 1),
+    }
+}
+
+fn describe_heights(heights: &HashMap<u32, usize>) -> String {
+    let mut keys: Vec<&u32> = heights.keys().collect();
+    keys.sort();
+    let listed: Vec<String> = keys
+        .iter()
+        .map(|key| format!("{}={}", key, heights[key]))
+        .collect();
+    listed.join(", ")
+}
+
+/// Explains why `movement` cannot run on stacks of these heights.
+fn problem(heights: &HashMap<u32, usize>, movement: &Movement) -> Option<String> {
+    for stack in [movement.source, movement.target] {
+        if !heights.contains_key(&stack) {
+            return Some(format!("stack {} does not exist", stack));
+        }
+    }
+    let available = heights[&movement.source];
+    if available < movement.count as usize {
+        return Some(format!(
+            "stack {} holds only {} crates",
+            movement.source, available
+        ));
+    }
+    None
+}
+
+fn lift(heights: &mut HashMap<u32, usize>, movement: &Movement) {
+    *heights.get_mut(&movement.source).unwrap() -= movement.count as usize;
+    *heights.get_mut(&movement.target).unwrap() += movement.count as usize;
+}
+
+/// Dry-runs the moves against stack heights, failing on the first move that
+/// names an unknown stack or lifts more crates than its source holds.
+///
+/// `lines` gives the input line of each move, as returned by
+/// `parse_input_lines`; moves without one are reported by position.
+pub fn validate(state: &State, lines: &[usize]) -> Result<(), String> {
+    let mut heights: HashMap<u32, usize> = state
+        .stacks
+        .iter()
+        .map(|(key, stack)| (*key, stack.len()))
+        .collect();
+    for (index, movement) in state.moves.iter().enumerate() {
+        if let Some(message) = problem(&heights, movement) {
+            return Err(format!(
+                "{}: {}: {} (stack heights: {})",
+                location(lines, index),
+                movement,
+                message,
+                describe_heights(&heights)
+            ));
+        }
+        lift(&mut heights, movement);
+    }
+    Ok(())
+}
+
+/// Rewrites the moves so every one of them can run, returning the repaired
+/// state with a warning for each move that was changed or dropped.
+pub fn repair(state: State, lines: &[usize], mode: Repair) -> (State, Vec<String>) {
+    let mut heights: HashMap<u32, usize> = state
+        .stacks
+        .iter()
+        .map(|(key, stack)| (*key, stack.len()))
+        .collect();
+    let mut warnings = Vec::new();
+    let mut moves = Vec::new();
+    for (index, movement) in state.moves.into_iter().enumerate() {
+        let message = match problem(&heights, &movement) {
+            Some(message) => message,
+            None => {
+                lift(&mut heights, &movement);
+                moves.push(movement);
+                continue;
+            }
+        };
+        let known =
+            heights.contains_key(&movement.source) && heights.contains_key(&movement.target);
+        let available = heights.get(&movement.source).copied().unwrap_or(0);
+        let action = if mode == Repair::Clamp && known && available > 0 {
+            let clamped = Movement::new(available as u32, movement.source, movement.target);
+            lift(&mut heights, &clamped);
+            moves.push(clamped);
+            format!("clamped to {} crates", available)
+        } else {
+            "skipped".to_string()
+        };
+        warnings.push(format!(
+            "{}: {}: {}, {}",
+            location(lines, index),
+            movement,
+            message,
+            action
+        ));
+    }
+    let repaired = State {
+        stacks: state.stacks,
+        moves: moves.into(),
+    };
+    (repaired, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{apply_moves, parse_input_lines, CrateMover9000};
+    use super::*;
+
+    const EXAMPLE: &str = "
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn day05_validate() {
+        let (state, lines) = parse_input_lines(EXAMPLE).unwrap();
+        assert_eq!(vec![7, 8, 9, 10], lines);
+        assert_eq!(Ok(()), validate(&state, &lines));
+
+        let input = EXAMPLE.replace("move 2 from 2 to 1", "move 3 from 2 to 1");
+        let (state, lines) = parse_input_lines(&input).unwrap();
+        assert_eq!(
+            Err("line 9: move 3 from 2 to 1: stack 2 holds only 2 crates \
+                 (stack heights: 1=0, 2=2, 3=4)"
+                .to_string()),
+            validate(&state, &lines)
+        );
+
+        let input = EXAMPLE.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+        let (state, _) = parse_input_lines(&input).unwrap();
+        assert_eq!(
+            Err("move 4: move 1 from 1 to 4: stack 4 does not exist \
+                 (stack heights: 1=2, 2=0, 3=4)"
+                .to_string()),
+            validate(&state, &[])
+        );
+    }
+
+    #[test]
+    fn day05_repair() {
+        let input = EXAMPLE
+            .replace("move 2 from 2 to 1", "move 3 from 2 to 1")
+            .replace(
+                "move 3 from 1 to 3",
+                "move 3 from 7 to 3\nmove 5 from 1 to 3",
+            );
+        let (state, lines) = parse_input_lines(&input).unwrap();
+        assert!(validate(&state, &lines).is_err());
+
+        let (clamped, warnings) = repair(state.clone(), &lines, Repair::Clamp);
+        assert_eq!(
+            vec![
+                "line 8: move 3 from 7 to 3: stack 7 does not exist, skipped",
+                "line 9: move 5 from 1 to 3: stack 1 holds only 3 crates, clamped to 3 crates",
+                "line 10: move 3 from 2 to 1: stack 2 holds only 2 crates, clamped to 2 crates",
+            ],
+            warnings
+        );
+        assert_eq!(Ok(()), validate(&clamped, &[]));
+        assert_eq!(4, clamped.moves.len());
+        assert_eq!(
+            vec!["C", "M", "Z"],
+            apply_moves(clamped, &CrateMover9000).top_items()
+        );
+
+        let (skipped, warnings) = repair(state, &lines, Repair::Skip);
+        assert_eq!(3, warnings.len());
+        assert!(warnings[1].ends_with("stack 1 holds only 3 crates, skipped"));
+        assert_eq!(
+            vec![Movement::new(1, 2, 1), Movement::new(1, 1, 2)],
+            Vec::from(skipped.moves.clone())
+        );
+        assert_eq!(Ok(()), validate(&skipped, &[]));
+    }
+}
@@ -0,0 +1,175 @@
+//! Tuning trouble: markers of distinct characters in the device signal.
+
+use std::io::{self, Read};
+
+use crate::solution::Solution;
+use crate::window::DistinctWindow;
+
+pub const PACKET_MARKER: usize = 4;
+pub const MESSAGE_MARKER: usize = 14;
+
+/// Number of characters read when the last `size` were first all different,
+/// ignoring whitespace such as the trailing newline.
+pub fn find_marker(signal: &[u8], size: usize) -> Option<usize> {
+    let mut window = DistinctWindow::new(size);
+    signal
+        .iter()
+        .filter(|x| !x.is_ascii_whitespace())
+        .position(|x| window.push(*x))
+        .map(|i| i + 1)
+}
+
+/// Same as `find_marker`, reading the signal a chunk at a time so it never
+/// has to fit in memory.
+pub fn read_marker<R: Read>(mut reader: R, size: usize) -> io::Result<Option<usize>> {
+    let mut window = DistinctWindow::new(size);
+    let mut buffer = [0; 8192];
+    let mut position = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for byte in &buffer[..read] {
+            if byte.is_ascii_whitespace() {
+                continue;
+            }
+            position += 1;
+            if window.push(*byte) {
+                return Ok(Some(position));
+            }
+        }
+    }
+}
+
+/// Checks every window from scratch, comparing each pair of characters.
+pub fn naive_marker(signal: &[u8], size: usize) -> Option<usize> {
+    let signal: Vec<u8> = signal
+        .iter()
+        .copied()
+        .filter(|x| !x.is_ascii_whitespace())
+        .collect();
+    (size..=signal.len()).find(|end| {
+        let window = &signal[end - size..*end];
+        (0..size).all(|i| (i + 1..size).all(|j| window[i] != window[j]))
+    })
+}
+
+fn answer(marker: Option<usize>) -> Result<String, String> {
+    marker
+        .map(|x| x.to_string())
+        .ok_or("No marker in the signal".to_string())
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 6, 'a', "window", |text| {
+            answer(find_marker(text.as_bytes(), PACKET_MARKER))
+        }),
+        Solution::new(2022, 6, 'a', "naive", |text| {
+            answer(naive_marker(text.as_bytes(), PACKET_MARKER))
+        }),
+        Solution::new(2022, 6, 'b', "window", |text| {
+            answer(find_marker(text.as_bytes(), MESSAGE_MARKER))
+        }),
+        Solution::new(2022, 6, 'b', "naive", |text| {
+            answer(naive_marker(text.as_bytes(), MESSAGE_MARKER))
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop;
+    use crate::rng::Rng;
+
+    /// `length` characters drawn from the first `letters` lowercase ones,
+    /// so small alphabets repeat often and markers come late or not at all.
+    fn signal(rng: &mut Rng, length: usize, letters: u8) -> String {
+        (0..length)
+            .map(|_| (b'a' + rng.below(letters as u64) as u8) as char)
+            .collect()
+    }
+
+    const EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    /// Hands out one byte per read, so every marker straddles a chunk.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((first, rest)) if !buf.is_empty() => {
+                    buf[0] = *first;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn day06_examples() {
+        for (signal, packet, message) in EXAMPLES {
+            let signal = format!("{}\n", signal);
+            assert_eq!(Some(packet), find_marker(signal.as_bytes(), PACKET_MARKER));
+            assert_eq!(
+                Some(message),
+                find_marker(signal.as_bytes(), MESSAGE_MARKER)
+            );
+            let streamed = read_marker(Trickle(signal.as_bytes()), MESSAGE_MARKER);
+            assert_eq!(Some(message), streamed.unwrap());
+            for solution in solutions() {
+                let expected = if solution.part == 'a' {
+                    packet
+                } else {
+                    message
+                };
+                assert_eq!(Ok(expected.to_string()), (solution.run)(&signal));
+            }
+        }
+        assert_eq!(None, find_marker(b"abcabc", PACKET_MARKER));
+        assert_eq!(
+            Ok(None),
+            read_marker(&b"aaaa\n"[..], 2).map_err(|e| e.kind())
+        );
+    }
+
+    #[test]
+    fn day06_long_stream() {
+        // Megabytes of one repeated letter, completed to a marker at the end.
+        let mut signal = b"a".repeat(3 << 20);
+        signal.extend_from_slice(b"bcd");
+        let expected = Some(signal.len());
+        assert_eq!(expected, read_marker(&signal[..], PACKET_MARKER).unwrap());
+    }
+
+    #[test]
+    fn day06_matches_naive() {
+        prop::check(
+            6,
+            2000,
+            |rng| {
+                let length = rng.below(60) as usize;
+                let size = 1 + rng.below(6) as usize;
+                (signal(rng, length, 8), size)
+            },
+            |(signal, size)| {
+                let expected = naive_marker(signal.as_bytes(), *size);
+                prop::expect_eq(expected, find_marker(signal.as_bytes(), *size))?;
+                let streamed = read_marker(Trickle(signal.as_bytes()), *size);
+                prop::expect_eq(expected, streamed.map_err(|e| e.to_string())?)
+            },
+        );
+    }
+}
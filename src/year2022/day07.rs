@@ -0,0 +1,625 @@
+//! No space left on device: the filesystem behind a `cd`/`ls` transcript.
+
+use std::fmt;
+
+use crate::solution::Solution;
+
+pub type NodeId = usize;
+
+pub const DISK_SIZE: u64 = 70_000_000;
+pub const UPDATE_SIZE: u64 = 30_000_000;
+pub const SMALL_DIR: u64 = 100_000;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NodeKind {
+    Dir { children: Vec<NodeId> },
+    File { size: u64 },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+}
+
+/// Directory tree with every node in one arena, linked to its parent so `cd ..`
+/// is a lookup rather than a path walk.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    /// An empty filesystem holding only the root directory `/`.
+    pub fn new() -> FileSystem {
+        let root = Node {
+            name: "/".to_string(),
+            parent: None,
+            kind: NodeKind::Dir {
+                children: Vec::new(),
+            },
+        };
+        FileSystem { nodes: vec![root] }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// Number of files and directories below the root, so node ids run from
+    /// `ROOT` to `len()` inclusive.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Dir { .. })
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            NodeKind::Dir { children } => children,
+            NodeKind::File { .. } => &[],
+        }
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|x| self.nodes[*x].name == name)
+    }
+
+    fn add(&mut self, parent: NodeId, name: &str, kind: NodeKind) -> Result<NodeId, String> {
+        if let Some(existing) = self.child(parent, name) {
+            let same = match (&self.nodes[existing].kind, &kind) {
+                (NodeKind::Dir { .. }, NodeKind::Dir { .. }) => true,
+                (existing, added) => existing == added,
+            };
+            if !same {
+                let path = self.path(existing);
+                return Err(format!("{} is listed twice with different contents", path));
+            }
+            return Ok(existing);
+        }
+        if !self.is_dir(parent) {
+            return Err(format!("{} is not a directory", self.path(parent)));
+        }
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        if let NodeKind::Dir { children } = &mut self.nodes[parent].kind {
+            children.push(id);
+        }
+        Ok(id)
+    }
+
+    /// Adds a directory, or returns the existing one when listed again.
+    pub fn add_dir(&mut self, parent: NodeId, name: &str) -> Result<NodeId, String> {
+        let kind = NodeKind::Dir {
+            children: Vec::new(),
+        };
+        self.add(parent, name, kind)
+    }
+
+    /// Adds a file, or returns the existing one when listed again with the
+    /// same size.
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: u64) -> Result<NodeId, String> {
+        self.add(parent, name, NodeKind::File { size })
+    }
+
+    /// Absolute path, with a trailing `/` for directories.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(id);
+        while let Some(node) = current {
+            if node != Self::ROOT {
+                names.push(self.nodes[node].name.as_str());
+            }
+            current = self.nodes[node].parent;
+        }
+        names.reverse();
+        let mut path = format!("/{}", names.join("/"));
+        if self.is_dir(id) && id != Self::ROOT {
+            path.push('/');
+        }
+        path
+    }
+
+    /// Total size of every node, indexed by id. Children are always added
+    /// after their parent, so one backwards pass aggregates the whole tree.
+    pub fn sizes(&self) -> Vec<u64> {
+        let mut sizes: Vec<u64> = self
+            .nodes
+            .iter()
+            .map(|x| match x.kind {
+                NodeKind::File { size } => size,
+                NodeKind::Dir { .. } => 0,
+            })
+            .collect();
+        for id in (1..self.nodes.len()).rev() {
+            let parent = self.nodes[id].parent.unwrap();
+            sizes[parent] += sizes[id];
+        }
+        sizes
+    }
+
+    /// Total size of each directory, in id order starting with the root.
+    pub fn dir_sizes(&self) -> Vec<(NodeId, u64)> {
+        self.sizes()
+            .into_iter()
+            .enumerate()
+            .filter(|(id, _)| self.is_dir(*id))
+            .collect()
+    }
+
+    /// Children sorted by name, as the puzzle lists them.
+    fn sorted_children(&self, id: NodeId) -> Vec<NodeId> {
+        let mut children = self.children(id).to_vec();
+        children.sort_by(|a, b| self.nodes[*a].name.cmp(&self.nodes[*b].name));
+        children
+    }
+
+    /// The tree in the puzzle's `- / (dir)` listing format.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        let mut pending = vec![(Self::ROOT, 0)];
+        while let Some((id, depth)) = pending.pop() {
+            let node = &self.nodes[id];
+            let detail = match node.kind {
+                NodeKind::Dir { .. } => "dir".to_string(),
+                NodeKind::File { size } => format!("file, size={}", size),
+            };
+            text += &format!("{}- {} ({})\n", "  ".repeat(depth), node.name, detail);
+            for child in self.sorted_children(id).into_iter().rev() {
+                pending.push((child, depth + 1));
+            }
+        }
+        text
+    }
+
+    /// A transcript exploring the whole tree depth first, which parses back
+    /// into an equal filesystem.
+    pub fn transcript(&self) -> String {
+        let mut text = "$ cd /\n".to_string();
+        self.write_transcript(Self::ROOT, &mut text);
+        text
+    }
+
+    fn write_transcript(&self, id: NodeId, text: &mut String) {
+        text.push_str("$ ls\n");
+        for child in self.children(id) {
+            let node = &self.nodes[*child];
+            match node.kind {
+                NodeKind::Dir { .. } => *text += &format!("dir {}\n", node.name),
+                NodeKind::File { size } => *text += &format!("{} {}\n", size, node.name),
+            }
+        }
+        for child in self.children(id) {
+            if self.is_dir(*child) {
+                *text += &format!("$ cd {}\n", self.nodes[*child].name);
+                self.write_transcript(*child, text);
+                text.push_str("$ cd ..\n");
+            }
+        }
+    }
+}
+
+impl fmt::Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+/// Rebuilds the filesystem from a transcript, rejecting `cd` into directories
+/// no `ls` has shown yet.
+pub fn parse_input(text: &str) -> Result<FileSystem, String> {
+    let mut fs = FileSystem::new();
+    let mut cwd = FileSystem::ROOT;
+    let mut listing = false;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: String| format!("line {}: {}", i + 1, message);
+        if let Some(command) = line.strip_prefix("$ ") {
+            listing = false;
+            match command.split_once(' ') {
+                None if command == "ls" => listing = true,
+                Some(("cd", "/")) => cwd = FileSystem::ROOT,
+                Some(("cd", "..")) => match fs.node(cwd).parent {
+                    Some(parent) => cwd = parent,
+                    None => return Err(error("cd .. from the root directory".to_string())),
+                },
+                Some(("cd", name)) => match fs.child(cwd, name) {
+                    Some(dir) if fs.is_dir(dir) => cwd = dir,
+                    Some(_) => return Err(error(format!("cd into file {}", name))),
+                    None => {
+                        return Err(error(format!(
+                            "cd into unknown directory {} in {}",
+                            name,
+                            fs.path(cwd)
+                        )))
+                    }
+                },
+                _ => return Err(error(format!("unknown command {:?}", command))),
+            }
+            continue;
+        }
+        if !listing {
+            return Err(error(format!("output {:?} outside of ls", line)));
+        }
+        let added = match line.split_once(' ') {
+            Some(("dir", name)) => fs.add_dir(cwd, name),
+            Some((size, name)) => match size.parse() {
+                Ok(size) => fs.add_file(cwd, name, size),
+                Err(_) => Err(format!("bad file size {:?}", size)),
+            },
+            None => Err(format!("unknown listing {:?}", line)),
+        };
+        added.map_err(error)?;
+    }
+    Ok(fs)
+}
+
+/// Sum of the directories no larger than `limit`, counting nested ones again.
+pub fn small_dirs_total(fs: &FileSystem, limit: u64) -> u64 {
+    fs.dir_sizes()
+        .into_iter()
+        .map(|(_, size)| size)
+        .filter(|x| *x <= limit)
+        .sum()
+}
+
+/// Size of the smallest directory whose deletion frees enough for the update.
+pub fn dir_to_delete(fs: &FileSystem, disk: u64, update: u64) -> Option<u64> {
+    let sizes: Vec<u64> = fs.dir_sizes().into_iter().map(|(_, size)| size).collect();
+    let needed = (update + sizes[0]).saturating_sub(disk);
+    sizes.into_iter().filter(|x| *x >= needed).min()
+}
+
+/// Directory sizes straight from the transcript without building the tree:
+/// a stack of running totals, folded into the parent on every `cd ..`.
+///
+/// Assumes each directory is listed once and every `cd` into a directory is
+/// valid, but rejects `cd ..` from the root and lines it cannot read.
+pub fn stack_dir_sizes(text: &str) -> Result<Vec<u64>, String> {
+    fn close(stack: &mut Vec<u64>, sizes: &mut Vec<u64>) {
+        let size = stack.pop().unwrap();
+        *stack.last_mut().unwrap() += size;
+        sizes.push(size);
+    }
+
+    let mut stack = vec![0];
+    let mut sizes = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: String| format!("line {}: {}", i + 1, message);
+        if let Some(command) = line.strip_prefix("$ ") {
+            match command.split_once(' ') {
+                None if command == "ls" => {}
+                Some(("cd", "/")) => {
+                    while stack.len() > 1 {
+                        close(&mut stack, &mut sizes);
+                    }
+                }
+                Some(("cd", "..")) if stack.len() == 1 => {
+                    return Err(error("cd .. from the root directory".to_string()))
+                }
+                Some(("cd", "..")) => close(&mut stack, &mut sizes),
+                Some(("cd", _)) => stack.push(0),
+                _ => return Err(error(format!("unknown command {:?}", command))),
+            }
+            continue;
+        }
+        match line.split_once(' ') {
+            Some(("dir", _)) => {}
+            Some((size, _)) => match size.parse::<u64>() {
+                Ok(size) => *stack.last_mut().unwrap() += size,
+                Err(_) => return Err(error(format!("bad file size {:?}", size))),
+            },
+            None => return Err(error(format!("unknown listing {:?}", line))),
+        }
+    }
+    while stack.len() > 1 {
+        close(&mut stack, &mut sizes);
+    }
+    sizes.push(stack[0]);
+    sizes.reverse();
+    Ok(sizes)
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 7, 'a', "tree", |text| {
+            Ok(small_dirs_total(&parse_input(text)?, SMALL_DIR).to_string())
+        }),
+        Solution::new(2022, 7, 'a', "stack", |text| {
+            let sizes = stack_dir_sizes(text)?;
+            Ok(sizes
+                .iter()
+                .filter(|x| **x <= SMALL_DIR)
+                .sum::<u64>()
+                .to_string())
+        }),
+        Solution::new(2022, 7, 'b', "tree", |text| {
+            dir_to_delete(&parse_input(text)?, DISK_SIZE, UPDATE_SIZE)
+                .map(|x| x.to_string())
+                .ok_or("No directory is large enough".to_string())
+        }),
+        Solution::new(2022, 7, 'b', "stack", |text| {
+            let sizes = stack_dir_sizes(text)?;
+            let needed = (UPDATE_SIZE + sizes[0]).saturating_sub(DISK_SIZE);
+            sizes
+                .into_iter()
+                .filter(|x| *x >= needed)
+                .min()
+                .map(|x| x.to_string())
+                .ok_or("No directory is large enough".to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop::{self, Shrink};
+    use crate::rng::Rng;
+
+    /// Copy without the subtree at `removed`, renumbering the remaining nodes.
+    fn without(fs: &FileSystem, removed: NodeId) -> FileSystem {
+        let mut copy = FileSystem::new();
+        let mut pending = vec![(FileSystem::ROOT, FileSystem::ROOT)];
+        while let Some((id, target)) = pending.pop() {
+            for child in fs.children(id) {
+                if *child == removed {
+                    continue;
+                }
+                let node = &fs.nodes[*child];
+                let added = match node.kind {
+                    NodeKind::Dir { .. } => copy.add_dir(target, &node.name),
+                    NodeKind::File { size } => copy.add_file(target, &node.name, size),
+                };
+                pending.push((*child, added.unwrap()));
+            }
+        }
+        copy
+    }
+
+    impl Shrink for FileSystem {
+        /// Drops whole subtrees, largest first, then halves file sizes.
+        fn shrink(&self) -> Vec<Self> {
+            let mut candidates: Vec<Self> =
+                (1..self.nodes.len()).map(|x| without(self, x)).collect();
+            for (id, node) in self.nodes.iter().enumerate() {
+                if let NodeKind::File { size } = node.kind {
+                    for smaller in size.shrink() {
+                        let mut candidate = self.clone();
+                        candidate.nodes[id].kind = NodeKind::File { size: smaller };
+                        candidates.push(candidate);
+                    }
+                }
+            }
+            candidates
+        }
+    }
+
+    /// `dirs` directories and `files` files with unique names, each placed
+    /// under a random earlier directory.
+    fn file_tree(rng: &mut Rng, dirs: usize, files: usize) -> FileSystem {
+        let mut fs = FileSystem::new();
+        let mut parents = vec![FileSystem::ROOT];
+        for i in 0..dirs {
+            let parent = parents[rng.below(parents.len() as u64) as usize];
+            parents.push(fs.add_dir(parent, &format!("d{}", i)).unwrap());
+        }
+        for i in 0..files {
+            let parent = parents[rng.below(parents.len() as u64) as usize];
+            let size = 1 + rng.below(200_000);
+            fs.add_file(parent, &format!("f{}.txt", i), size).unwrap();
+        }
+        fs
+    }
+
+    const EXAMPLE: &str = "
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn day07_example1() {
+        let fs = parse_input(EXAMPLE).unwrap();
+        let e = fs
+            .child(fs.child(FileSystem::ROOT, "a").unwrap(), "e")
+            .unwrap();
+        assert_eq!("/a/e/", fs.path(e));
+        assert_eq!(
+            Some(FileSystem::ROOT),
+            fs.node(fs.node(e).parent.unwrap()).parent
+        );
+        let sizes = fs.sizes();
+        assert_eq!(584, sizes[e]);
+        assert_eq!(48381165, sizes[FileSystem::ROOT]);
+        assert_eq!(95437, small_dirs_total(&fs, SMALL_DIR));
+        assert_eq!(Some(24933642), dir_to_delete(&fs, DISK_SIZE, UPDATE_SIZE));
+        for solution in solutions() {
+            let expected = if solution.part == 'a' {
+                "95437"
+            } else {
+                "24933642"
+            };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day07_render() {
+        let fs = parse_input(EXAMPLE).unwrap();
+        let expected = "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+        assert_eq!(expected, fs.to_string());
+        assert_eq!(fs, parse_input(&fs.transcript()).unwrap());
+    }
+
+    #[test]
+    fn day07_validation_errors() {
+        let errors = [
+            ("$ cd /\n$ cd a", "line 2: cd into unknown directory a in /"),
+            (
+                "$ ls\ndir a\n$ cd a\n$ ls\n5 x\n$ cd b",
+                "line 6: cd into unknown directory b in /a/",
+            ),
+            ("$ cd ..", "line 1: cd .. from the root directory"),
+            ("$ ls\n5 x\n$ cd x", "line 3: cd into file x"),
+            ("$ rm -rf /", "line 1: unknown command \"rm -rf /\""),
+            ("$ cd /\ndir a", "line 2: output \"dir a\" outside of ls"),
+            ("$ ls\nbig x", "line 2: bad file size \"big\""),
+            (
+                "$ ls\n5 x\n$ ls\n6 x",
+                "line 4: /x is listed twice with different contents",
+            ),
+        ];
+        for (transcript, error) in errors {
+            assert_eq!(Err(error.to_string()), parse_input(transcript));
+        }
+        let errors = [
+            ("$ cd ..", "line 1: cd .. from the root directory"),
+            (
+                "$ cd a\n$ cd ..\n$ cd ..",
+                "line 3: cd .. from the root directory",
+            ),
+            ("$ rm -rf /", "line 1: unknown command \"rm -rf /\""),
+            ("$ ls\nbig x", "line 2: bad file size \"big\""),
+            ("$ ls\n5", "line 2: unknown listing \"5\""),
+        ];
+        for (transcript, error) in errors {
+            assert_eq!(Err(error.to_string()), stack_dir_sizes(transcript));
+        }
+        for solution in solutions() {
+            assert_eq!(
+                Err("line 1: cd .. from the root directory".to_string()),
+                (solution.run)("$ cd ..")
+            );
+        }
+        // Listing a directory again is fine and does not count files twice.
+        let fs = parse_input("$ ls\n5 x\ndir a\n$ ls\n5 x\ndir a").unwrap();
+        assert_eq!(2, fs.len());
+        assert!(!fs.is_empty());
+        assert_eq!(5, fs.sizes()[FileSystem::ROOT]);
+        // The root alone is an empty filesystem.
+        let fs = parse_input("$ cd /\n$ ls").unwrap();
+        assert_eq!(0, fs.len());
+        assert!(fs.is_empty());
+    }
+
+    /// Each directory's size as the sum of every file below its path.
+    fn naive_sizes(fs: &FileSystem) -> Vec<(String, u64)> {
+        let files: Vec<(String, u64)> = (FileSystem::ROOT..=fs.len())
+            .filter_map(|x| match fs.node(x).kind {
+                NodeKind::File { size } => Some((fs.path(x), size)),
+                NodeKind::Dir { .. } => None,
+            })
+            .collect();
+        let mut sizes: Vec<(String, u64)> = (FileSystem::ROOT..=fs.len())
+            .filter(|x| fs.is_dir(*x))
+            .map(|x| {
+                let path = fs.path(x);
+                let size = files
+                    .iter()
+                    .filter(|(file, _)| file.starts_with(&path))
+                    .map(|(_, size)| size)
+                    .sum();
+                (path, size)
+            })
+            .collect();
+        sizes.sort();
+        sizes
+    }
+
+    #[test]
+    fn day07_matches_naive() {
+        prop::check(
+            7,
+            500,
+            |rng| {
+                let dirs = rng.below(12) as usize;
+                let files = rng.below(20) as usize;
+                file_tree(rng, dirs, files)
+            },
+            |fs| {
+                let mut sizes: Vec<(String, u64)> = fs
+                    .dir_sizes()
+                    .into_iter()
+                    .map(|(id, size)| (fs.path(id), size))
+                    .collect();
+                sizes.sort();
+                prop::expect_eq(naive_sizes(fs), sizes)?;
+                let transcript = fs.transcript();
+                prop::expect_eq(
+                    Ok(fs.render()),
+                    parse_input(&transcript).map(|x| x.render()),
+                )?;
+                let mut expected: Vec<u64> = fs.dir_sizes().into_iter().map(|x| x.1).collect();
+                let mut stacked = stack_dir_sizes(&transcript)?;
+                expected.sort();
+                stacked.sort();
+                prop::expect_eq(expected, stacked)
+            },
+        );
+    }
+}
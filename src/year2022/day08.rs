@@ -0,0 +1,185 @@
+//! Treetop tree house: which trees are visible from outside the grid and how
+//! far each one can see.
+
+use crate::solution::Solution;
+use crate::{Direction, Grid};
+
+pub fn parse_input(text: &str) -> Result<Grid<u8>, String> {
+    Grid::parse(text.trim(), |c| match c.to_digit(10) {
+        Some(height) => Ok(height as u8),
+        None => Err(format!("not a tree height: {:?}", c)),
+    })
+}
+
+/// What every tree sees looking in one direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sight {
+    /// No tree at least as tall stands between it and the edge.
+    pub visible: Grid<bool>,
+    /// Trees until the first one at least as tall, or until the edge.
+    pub distance: Grid<usize>,
+}
+
+/// Sight toward `direction` for every tree in linear time.
+///
+/// Each line is walked away from the edge it looks at, keeping a stack of the
+/// trees behind the current one in strictly decreasing height. Trees shorter
+/// than the current one can never block anything past it, so they are popped
+/// for good, and whatever remains on top is the first tree that blocks it.
+pub fn sight(grid: &Grid<u8>, direction: Direction) -> Sight {
+    let mut visible = Grid::filled(grid.width(), grid.height(), false);
+    let mut distance = Grid::filled(grid.width(), grid.height(), 0);
+    for start in grid.edge(direction) {
+        let line = std::iter::once(start).chain(grid.ray(start, direction.opposite()));
+        let mut stack: Vec<(usize, u8)> = Vec::new();
+        for (i, position) in line.enumerate() {
+            let height = grid[position];
+            while stack.last().is_some_and(|x| x.1 < height) {
+                stack.pop();
+            }
+            match stack.last() {
+                Some((blocker, _)) => distance[position] = i - blocker,
+                None => {
+                    visible[position] = true;
+                    distance[position] = i;
+                }
+            }
+            stack.push((i, height));
+        }
+    }
+    Sight { visible, distance }
+}
+
+/// Number of trees visible from at least one edge.
+pub fn count_visible(grid: &Grid<u8>) -> usize {
+    let sights: Vec<Sight> = Direction::ALL.iter().map(|x| sight(grid, *x)).collect();
+    grid.positions()
+        .filter(|x| sights.iter().any(|sight| sight.visible[*x]))
+        .count()
+}
+
+/// Highest product of the four viewing distances of any tree.
+pub fn best_scenic_score(grid: &Grid<u8>) -> usize {
+    let sights: Vec<Sight> = Direction::ALL.iter().map(|x| sight(grid, *x)).collect();
+    grid.positions()
+        .map(|x| sights.iter().map(|sight| sight.distance[x]).product())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Looks along each ray from the tree until something blocks it, which is
+/// quadratic in the grid side for every tree.
+pub fn scan_visible(grid: &Grid<u8>) -> usize {
+    grid.positions()
+        .filter(|x| {
+            Direction::ALL
+                .iter()
+                .any(|d| grid.ray(*x, *d).all(|y| grid[y] < grid[*x]))
+        })
+        .count()
+}
+
+pub fn scan_scenic_score(grid: &Grid<u8>) -> usize {
+    grid.positions()
+        .map(|x| {
+            Direction::ALL
+                .iter()
+                .map(|d| {
+                    let mut count = 0;
+                    for y in grid.ray(x, *d) {
+                        count += 1;
+                        if grid[y] >= grid[x] {
+                            break;
+                        }
+                    }
+                    count
+                })
+                .product()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 8, 'a', "stack", |text| {
+            Ok(count_visible(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 8, 'a', "scan", |text| {
+            Ok(scan_visible(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 8, 'b', "stack", |text| {
+            Ok(best_scenic_score(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 8, 'b', "scan", |text| {
+            Ok(scan_scenic_score(&parse_input(text)?).to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{generate, prop};
+
+    /// `height` rows of `width` digits.
+    fn digit_map(rng: &mut Rng, width: usize, height: usize) -> Vec<String> {
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| (b'0' + rng.below(10) as u8) as char)
+                    .collect()
+            })
+            .collect()
+    }
+
+    const EXAMPLE: &str = "
+30373
+25512
+65332
+33549
+35390
+";
+
+    #[test]
+    fn day08_example1() {
+        let grid = parse_input(EXAMPLE).unwrap();
+        assert_eq!(21, count_visible(&grid));
+        assert_eq!(8, best_scenic_score(&grid));
+        let up = sight(&grid, Direction::Up);
+        // The 5 in the middle of the fourth row.
+        assert_eq!(2, up.distance[(2, 3)]);
+        assert!(!up.visible[(2, 3)]);
+        assert!(sight(&grid, Direction::Left).visible[(1, 1)]);
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "21" } else { "8" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+        assert_eq!(
+            Err("line 2: not a tree height: 'x'".to_string()),
+            parse_input("12\n3x")
+        );
+    }
+
+    #[test]
+    fn day08_matches_scan() {
+        prop::check(
+            8,
+            1000,
+            |rng| {
+                let width = 1 + rng.below(8) as usize;
+                let height = 1 + rng.below(8) as usize;
+                digit_map(rng, width, height)
+            },
+            |rows| {
+                let grid = match parse_input(&generate::lines_input(rows)) {
+                    Ok(grid) => grid,
+                    Err(_) => return Ok(()),
+                };
+                prop::expect_eq(scan_visible(&grid), count_visible(&grid))?;
+                prop::expect_eq(scan_scenic_score(&grid), best_scenic_score(&grid))
+            },
+        );
+    }
+}
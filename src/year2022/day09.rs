@@ -0,0 +1,428 @@
+//! Rope bridge: knots dragged along by the head of a rope.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::solution::Solution;
+use crate::{load_input, Direction};
+
+/// A cell on the unbounded plane, with `y` growing downward as in `Grid`.
+pub type Point = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Motion {
+    pub direction: Direction,
+    pub steps: u32,
+}
+
+impl fmt::Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match self.direction {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        };
+        write!(f, "{} {}", letter, self.steps)
+    }
+}
+
+pub fn parse_input(text: &str) -> Result<Vec<Motion>, String> {
+    let mut motions = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = || format!("line {}: not a motion: {:?}", i + 1, line);
+        let (letter, steps) = line.split_once(' ').ok_or_else(error)?;
+        let direction = match letter {
+            "U" => Direction::Up,
+            "D" => Direction::Down,
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            _ => return Err(error()),
+        };
+        let steps = steps.parse().map_err(|_| error())?;
+        motions.push(Motion { direction, steps });
+    }
+    Ok(motions)
+}
+
+/// Where a knot moves to keep up with the one ahead: nowhere while they
+/// touch, otherwise one step toward it, diagonally if needed.
+pub fn follow(knot: Point, leader: Point) -> Point {
+    let (dx, dy) = (leader.0 - knot.0, leader.1 - knot.1);
+    if dx.abs() <= 1 && dy.abs() <= 1 {
+        knot
+    } else {
+        (knot.0 + dx.signum(), knot.1 + dy.signum())
+    }
+}
+
+/// Inclusive corners of the area a diagram shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Frame {
+    /// The smallest frame holding every point, or just the origin.
+    pub fn around<I: IntoIterator<Item = Point>>(points: I) -> Frame {
+        let mut frame = Frame {
+            min: (0, 0),
+            max: (0, 0),
+        };
+        for (x, y) in points {
+            frame.min = (frame.min.0.min(x), frame.min.1.min(y));
+            frame.max = (frame.max.0.max(x), frame.max.1.max(y));
+        }
+        frame
+    }
+
+    pub fn union(self, other: Frame) -> Frame {
+        Frame::around([self.min, self.max, other.min, other.max])
+    }
+
+    /// Draws one character per cell, row by row, each row ending in `\n`.
+    fn draw<F: Fn(Point) -> char>(&self, cell: F) -> String {
+        let mut text = String::new();
+        for y in self.min.1..=self.max.1 {
+            for x in self.min.0..=self.max.0 {
+                text.push(cell((x, y)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// A rope of any number of knots starting on the origin, remembering every
+/// cell its tail has visited.
+#[derive(Debug, Clone)]
+pub struct Rope {
+    knots: Vec<Point>,
+    visited: HashSet<Point>,
+}
+
+impl Rope {
+    pub fn new(knots: usize) -> Result<Rope, String> {
+        if knots == 0 {
+            return Err("A rope needs at least one knot".to_string());
+        }
+        Ok(Rope {
+            knots: vec![(0, 0); knots],
+            visited: HashSet::from([(0, 0)]),
+        })
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn tail(&self) -> Point {
+        *self.knots.last().unwrap()
+    }
+
+    pub fn visited(&self) -> &HashSet<Point> {
+        &self.visited
+    }
+
+    /// Moves the head one cell and lets every other knot catch up, stopping
+    /// early once a knot stays put since nothing behind it can move either.
+    pub fn step(&mut self, direction: Direction) {
+        let (dx, dy) = direction.offset();
+        self.knots[0].0 += dx as i64;
+        self.knots[0].1 += dy as i64;
+        for i in 1..self.knots.len() {
+            let moved = follow(self.knots[i], self.knots[i - 1]);
+            if moved == self.knots[i] {
+                return;
+            }
+            self.knots[i] = moved;
+        }
+        self.visited.insert(self.tail());
+    }
+
+    pub fn apply(&mut self, motion: &Motion) {
+        for _ in 0..motion.steps {
+            self.step(motion.direction);
+        }
+    }
+
+    /// Label of the knot drawn on top: `H` for the head, then `T` for the tail
+    /// of a two knot rope or the knot's index on longer ones.
+    fn label(&self, index: usize) -> char {
+        match (index, self.knots.len()) {
+            (0, _) => 'H',
+            (_, 2) => 'T',
+            (i, _) => std::char::from_digit(i as u32 % 36, 36).unwrap(),
+        }
+    }
+
+    /// The rope as in the puzzle's diagrams: the frontmost knot on each cell,
+    /// `s` for an uncovered start and `.` elsewhere.
+    pub fn render(&self, frame: Frame) -> String {
+        frame.draw(|point| match self.knots.iter().position(|x| *x == point) {
+            Some(index) => self.label(index),
+            None if point == (0, 0) => 's',
+            None => '.',
+        })
+    }
+
+    /// Cells the tail visited as `#`, with `s` for the start.
+    pub fn render_visited(&self, frame: Frame) -> String {
+        frame.draw(|point| {
+            if point == (0, 0) {
+                's'
+            } else if self.visited.contains(&point) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+    }
+
+    /// Frame around the knots and every visited cell.
+    pub fn frame(&self) -> Frame {
+        Frame::around(self.knots.iter().chain(self.visited.iter()).copied())
+    }
+}
+
+/// Number of cells the tail of a `knots` long rope visits.
+pub fn tail_visits(motions: &[Motion], knots: usize) -> Result<usize, String> {
+    let mut rope = Rope::new(knots)?;
+    for motion in motions {
+        rope.apply(motion);
+    }
+    Ok(rope.visited().len())
+}
+
+/// Same as `tail_visits`, tracing the head's whole path and then deriving each
+/// following knot's path from the one before.
+pub fn chained_visits(motions: &[Motion], knots: usize) -> Result<usize, String> {
+    if knots == 0 {
+        return Err("A rope needs at least one knot".to_string());
+    }
+    let mut path = vec![(0, 0)];
+    for motion in motions {
+        let (dx, dy) = motion.direction.offset();
+        for _ in 0..motion.steps {
+            let (x, y) = *path.last().unwrap();
+            path.push((x + dx as i64, y + dy as i64));
+        }
+    }
+    for _ in 1..knots {
+        let mut knot = (0, 0);
+        path = path
+            .iter()
+            .map(|leader| {
+                knot = follow(knot, *leader);
+                knot
+            })
+            .collect();
+    }
+    path.sort();
+    path.dedup();
+    Ok(path.len())
+}
+
+/// Frame holding `rope` at every step of the motions, so traced diagrams
+/// line up.
+fn fit(rope: &Rope, motions: &[Motion]) -> Frame {
+    let mut rope = rope.clone();
+    let mut frame = rope.frame();
+    for motion in motions {
+        rope.apply(motion);
+        frame = frame.union(rope.frame());
+    }
+    frame
+}
+
+/// Command line of the day 9 bins: pulls a `default_knots` long rope through
+/// the motions unless another knot count is given, drawing every step with
+/// `--trace` and the visited cells with `--visited`. Exits with an error
+/// message on bad input.
+pub fn cli(default_knots: usize) {
+    let mut knots = default_knots;
+    let mut trace = false;
+    let mut visited = false;
+    for arg in std::env::args().skip(1) {
+        if arg == "--trace" {
+            trace = true;
+        } else if arg == "--visited" {
+            visited = true;
+        } else {
+            knots = arg.parse().expect("Knot count must be a number.");
+        }
+    }
+    let motions = match parse_input(&load_input(9)) {
+        Ok(motions) => motions,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let mut rope = match Rope::new(knots) {
+        Ok(rope) => rope,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let frame = fit(&rope, &motions);
+    for motion in &motions {
+        rope.apply(motion);
+        if trace {
+            println!("== {} ==\n\n{}", motion, rope.render(frame));
+        }
+    }
+    if visited {
+        println!("{}", rope.render_visited(frame));
+    }
+    println!("Answer: {}", rope.visited().len());
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 9, 'a', "rope", |text| {
+            tail_visits(&parse_input(text)?, 2).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 9, 'a', "chain", |text| {
+            chained_visits(&parse_input(text)?, 2).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 9, 'b', "rope", |text| {
+            tail_visits(&parse_input(text)?, 10).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 9, 'b', "chain", |text| {
+            chained_visits(&parse_input(text)?, 10).map(|x| x.to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prop;
+    use crate::rng::Rng;
+
+    /// `count` head motions as (`U`/`D`/`L`/`R`, steps) with up to
+    /// `max_steps` steps each.
+    fn motions(rng: &mut Rng, count: usize, max_steps: u32) -> Vec<(char, u32)> {
+        (0..count)
+            .map(|_| {
+                let letter = b"UDLR"[rng.below(4) as usize] as char;
+                (letter, 1 + rng.below(max_steps as u64) as u32)
+            })
+            .collect()
+    }
+
+    const EXAMPLE1: &str = "
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+    const EXAMPLE2: &str = "
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+    #[test]
+    fn day09_example1() {
+        let motions = parse_input(EXAMPLE1).unwrap();
+        assert_eq!("R 4", motions[0].to_string());
+        let mut rope = Rope::new(2).unwrap();
+        for motion in &motions {
+            rope.apply(motion);
+        }
+        assert_eq!(13, rope.visited().len());
+        let frame = Frame {
+            min: (0, -4),
+            max: (5, 0),
+        };
+        assert_eq!(
+            "......\n......\n.TH...\n......\ns.....\n",
+            rope.render(frame)
+        );
+        assert_eq!(
+            "..##..\n...##.\n.####.\n....#.\ns###..\n",
+            rope.render_visited(frame)
+        );
+        let fitted = Frame {
+            min: (0, -4),
+            max: (4, 0),
+        };
+        assert_eq!(fitted, rope.frame());
+        assert_eq!(frame, fitted.union(Frame::around([(5, -1)])));
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "13" } else { "1" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE1));
+        }
+    }
+
+    #[test]
+    fn day09_example2() {
+        let motions = parse_input(EXAMPLE2).unwrap();
+        let mut rope = Rope::new(10).unwrap();
+        for motion in &motions[..2] {
+            rope.apply(motion);
+        }
+        // The puzzle's diagram after `U 8`, trimmed to the rope.
+        let frame = Frame {
+            min: (0, -8),
+            max: (5, 0),
+        };
+        let expected = concat!(
+            ".....H\n", ".....1\n", ".....2\n", ".....3\n", "....54\n", "...6..\n", "..7...\n",
+            ".8....\n", "9.....\n",
+        );
+        assert_eq!(expected, rope.render(frame));
+        assert_eq!(Ok(36), tail_visits(&motions, 10));
+        assert_eq!(Ok(36), chained_visits(&motions, 10));
+        assert!(Rope::new(0).is_err());
+        assert_eq!(
+            Err("line 2: not a motion: \"X 3\"".to_string()),
+            parse_input("R 1\nX 3")
+        );
+    }
+
+    #[test]
+    fn day09_matches_chain() {
+        prop::check(
+            9,
+            1000,
+            |rng| {
+                let count = rng.below(30) as usize;
+                let knots = 1 + rng.below(12) as usize;
+                (motions(rng, count, 8), knots)
+            },
+            |(motions, knots)| {
+                let text: String = motions
+                    .iter()
+                    .map(|(c, n)| format!("{} {}\n", c, n))
+                    .collect();
+                let motions = match parse_input(&text) {
+                    Ok(motions) => motions,
+                    Err(_) => return Ok(()),
+                };
+                prop::expect_eq(
+                    chained_visits(&motions, *knots),
+                    tail_visits(&motions, *knots),
+                )
+            },
+        );
+    }
+}
@@ -0,0 +1,452 @@
+//! Cathode-ray tube: a one-register CPU whose X register steers the sprite a
+//! CRT draws letters with.
+
+use crate::ocr;
+use crate::solution::Solution;
+use crate::Grid;
+
+pub const SCREEN_WIDTH: usize = 40;
+pub const SCREEN_HEIGHT: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i64),
+}
+
+impl Instruction {
+    /// Cycles the instruction takes to complete.
+    pub fn cycles(self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+}
+
+pub fn parse_input(text: &str) -> Result<Vec<Instruction>, String> {
+    let mut program = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = || format!("line {}: not an instruction: {:?}", i + 1, line);
+        let instruction = match line.split_once(' ') {
+            None if line == "noop" => Instruction::Noop,
+            Some(("addx", value)) => Instruction::Addx(value.parse().map_err(|_| error())?),
+            _ => return Err(error()),
+        };
+        program.push(instruction);
+    }
+    Ok(program)
+}
+
+/// The CPU between cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cpu {
+    pub x: i64,
+    /// Cycles completed so far.
+    pub cycle: usize,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Cpu::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Cpu {
+        Cpu { x: 1, cycle: 0 }
+    }
+
+    /// Executes one instruction, calling `hook` with the number of each cycle,
+    /// counting from 1, and the value of X during it. An `addx` only changes X
+    /// once its second cycle is over.
+    pub fn execute<F: FnMut(usize, i64)>(&mut self, instruction: Instruction, mut hook: F) {
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            hook(self.cycle, self.x);
+        }
+        if let Instruction::Addx(value) = instruction {
+            self.x += value;
+        }
+    }
+
+    pub fn run<F: FnMut(usize, i64)>(&mut self, program: &[Instruction], mut hook: F) {
+        for instruction in program {
+            self.execute(*instruction, &mut hook);
+        }
+    }
+}
+
+/// Whether the cycle is one of those the puzzle samples X during.
+fn is_sampled(cycle: usize) -> bool {
+    cycle % 40 == 20 && cycle <= 220
+}
+
+/// Sum of the cycle number times X during cycles 20, 60, ... 220.
+pub fn signal_strength(program: &[Instruction]) -> i64 {
+    let mut total = 0;
+    Cpu::new().run(program, |cycle, x| {
+        if is_sampled(cycle) {
+            total += cycle as i64 * x;
+        }
+    });
+    total
+}
+
+/// Whether the three pixel wide sprite centered on X covers the pixel the CRT
+/// draws during `cycle`, and where that pixel is. Cycles past the last pixel
+/// draw nothing.
+fn pixel(cycle: usize, x: i64) -> Option<((usize, usize), bool)> {
+    let index = cycle - 1;
+    if index >= SCREEN_WIDTH * SCREEN_HEIGHT {
+        return None;
+    }
+    let column = index % SCREEN_WIDTH;
+    Some((
+        (column, index / SCREEN_WIDTH),
+        (column as i64 - x).abs() <= 1,
+    ))
+}
+
+/// The CRT after the program has run, drawing one pixel per cycle.
+pub fn draw_crt(program: &[Instruction]) -> Grid<bool> {
+    let mut screen = Grid::filled(SCREEN_WIDTH, SCREEN_HEIGHT, false);
+    Cpu::new().run(program, |cycle, x| {
+        if let Some((position, lit)) = pixel(cycle, x) {
+            screen[position] = lit;
+        }
+    });
+    screen
+}
+
+/// X during every cycle, the first cycle at index 0.
+pub fn trace(program: &[Instruction]) -> Vec<i64> {
+    let mut x = 1;
+    let mut values = Vec::new();
+    for instruction in program {
+        match instruction {
+            Instruction::Noop => values.push(x),
+            Instruction::Addx(value) => {
+                values.extend([x, x]);
+                x += value;
+            }
+        }
+    }
+    values
+}
+
+pub fn trace_signal_strength(program: &[Instruction]) -> i64 {
+    trace(program)
+        .iter()
+        .zip(1..)
+        .filter(|(_, cycle)| is_sampled(*cycle))
+        .map(|(x, cycle)| cycle as i64 * x)
+        .sum()
+}
+
+pub fn trace_crt(program: &[Instruction]) -> Grid<bool> {
+    let mut screen = Grid::filled(SCREEN_WIDTH, SCREEN_HEIGHT, false);
+    for (position, lit) in trace(program)
+        .iter()
+        .zip(1..)
+        .filter_map(|(x, cycle)| pixel(cycle, *x))
+    {
+        screen[position] = lit;
+    }
+    screen
+}
+
+/// Letters on the screen, or the screen itself when they can't be read.
+pub fn read_screen(screen: &Grid<bool>) -> Result<String, String> {
+    ocr::recognize(screen)
+        .map_err(|error| format!("{}\nThe screen shows:\n{}", error, ocr::render(screen)))
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 10, 'a', "cpu", |text| {
+            Ok(signal_strength(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 10, 'a', "trace", |text| {
+            Ok(trace_signal_strength(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 10, 'b', "cpu", |text| {
+            read_screen(&draw_crt(&parse_input(text)?))
+        }),
+        Solution::new(2022, 10, 'b', "trace", |text| {
+            read_screen(&trace_crt(&parse_input(text)?))
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{generate, prop};
+
+    /// `count` instructions, `noop` or `addx` with a small operand.
+    fn program(rng: &mut Rng, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| match rng.below(3) {
+                0 => "noop".to_string(),
+                _ => format!("addx {}", rng.below(21) as i64 - 10),
+            })
+            .collect()
+    }
+
+    const EXAMPLE: &str = "
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    const EXAMPLE_SCREEN: &str = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
+
+    /// A program whose CRT shows `screen`, changing X every other cycle. Each
+    /// pair of pixels is lit by where X sits while both are drawn, which is
+    /// always possible since rows have an even width.
+    fn program_drawing(screen: &Grid<bool>) -> String {
+        let targets: Vec<i64> = (0..SCREEN_WIDTH * SCREEN_HEIGHT / 2)
+            .map(|i| {
+                let column = (2 * i % SCREEN_WIDTH) as i64;
+                let row = 2 * i / SCREEN_WIDTH;
+                let left = screen[(column as usize, row)];
+                let right = screen[(column as usize + 1, row)];
+                match (left, right) {
+                    (true, true) => column + 1,
+                    (true, false) => column - 1,
+                    (false, true) => column + 2,
+                    (false, false) => -5,
+                }
+            })
+            .collect();
+        assert_eq!(1, targets[0], "the first pixel pair must suit X = 1");
+        targets
+            .windows(2)
+            .map(|x| format!("addx {}\n", x[1] - x[0]))
+            .collect()
+    }
+
+    #[test]
+    fn day10_small_program() {
+        let program = parse_input("noop\naddx 3\naddx -5").unwrap();
+        let mut cpu = Cpu::new();
+        let mut seen = Vec::new();
+        cpu.run(&program, |cycle, x| seen.push((cycle, x)));
+        assert_eq!(vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)], seen);
+        assert_eq!(Cpu { x: -1, cycle: 5 }, cpu);
+        assert_eq!(vec![1, 1, 1, 4, 4], trace(&program));
+        assert_eq!(
+            Err("line 2: not an instruction: \"addx\"".to_string()),
+            parse_input("noop\naddx")
+        );
+    }
+
+    #[test]
+    fn day10_example1() {
+        let program = parse_input(EXAMPLE).unwrap();
+        let values = trace(&program);
+        assert_eq!((21, 19, 18), (values[19], values[59], values[219]));
+        assert_eq!(13140, signal_strength(&program));
+        assert_eq!(EXAMPLE_SCREEN, ocr::render(&draw_crt(&program)));
+        // The example draws stripes rather than letters, so part two reports
+        // the screen instead of an answer.
+        for solution in solutions() {
+            let result = (solution.run)(EXAMPLE);
+            if solution.part == 'a' {
+                assert_eq!(Ok("13140".to_string()), result);
+            } else {
+                assert!(result.unwrap_err().ends_with(EXAMPLE_SCREEN));
+            }
+        }
+    }
+
+    #[test]
+    fn day10_reads_letters() {
+        let letters = ocr::draw("FEZ", ocr::Font::Small).unwrap();
+        let mut screen = Grid::filled(SCREEN_WIDTH, SCREEN_HEIGHT, false);
+        for position in letters.positions() {
+            screen[position] = letters[position];
+        }
+        let text = program_drawing(&screen);
+        assert_eq!(screen, draw_crt(&parse_input(&text).unwrap()));
+        for solution in solutions().iter().filter(|x| x.part == 'b') {
+            assert_eq!(Ok("FEZ".to_string()), (solution.run)(&text));
+        }
+    }
+
+    #[test]
+    fn day10_matches_trace() {
+        prop::check(
+            10,
+            1000,
+            |rng| {
+                let count = rng.below(200) as usize;
+                program(rng, count)
+            },
+            |lines| {
+                let program = match parse_input(&generate::lines_input(lines)) {
+                    Ok(program) => program,
+                    Err(_) => return Ok(()),
+                };
+                prop::expect_eq(trace_signal_strength(&program), signal_strength(&program))?;
+                prop::expect_eq(trace_crt(&program), draw_crt(&program))
+            },
+        );
+    }
+}
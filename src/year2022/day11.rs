@@ -0,0 +1,440 @@
+//! Monkey in the middle: monkeys throwing items around by how worried their
+//! owner is about each one.
+
+use std::fmt;
+
+use crate::math;
+use crate::solution::Solution;
+
+/// Right-hand side of a monkey's `new = ...` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Constant(u64),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parses space separated `old`, numbers, `+` and `*`, with `*` binding
+    /// tighter and both associating to the left.
+    pub fn parse(text: &str) -> Result<Expr, String> {
+        let error = || format!("not an expression: {:?}", text);
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut terms = Vec::new();
+        for term in tokens.split(|x| *x == "+") {
+            let mut factors = Vec::new();
+            for factor in term.split(|x| *x == "*") {
+                factors.push(match factor {
+                    ["old"] => Expr::Old,
+                    [number] => Expr::Constant(number.parse().map_err(|_| error())?),
+                    _ => return Err(error()),
+                });
+            }
+            terms.push(chain(factors, Expr::Mul));
+        }
+        Ok(chain(terms, Expr::Add))
+    }
+
+    /// The value for `old`, or `None` if it doesn't fit in a `u64`.
+    pub fn eval(&self, old: u64) -> Option<u64> {
+        match self {
+            Expr::Old => Some(old),
+            Expr::Constant(value) => Some(*value),
+            Expr::Add(a, b) => a.eval(old)?.checked_add(b.eval(old)?),
+            Expr::Mul(a, b) => a.eval(old)?.checked_mul(b.eval(old)?),
+        }
+    }
+}
+
+/// Joins non-empty `parts` left to right with `op`.
+fn chain(parts: Vec<Expr>, op: fn(Box<Expr>, Box<Expr>) -> Expr) -> Expr {
+    parts
+        .into_iter()
+        .reduce(|a, b| op(Box::new(a), Box::new(b)))
+        .unwrap()
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Old => write!(f, "old"),
+            Expr::Constant(value) => write!(f, "{}", value),
+            Expr::Add(a, b) => write!(f, "{} + {}", a, b),
+            Expr::Mul(a, b) => {
+                for (i, factor) in [a, b].into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " * ")?;
+                    }
+                    match **factor {
+                        Expr::Add(..) => write!(f, "({})", factor)?,
+                        _ => write!(f, "{}", factor)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
+    /// Worry levels of the items it starts with, in throwing order.
+    pub items: Vec<u64>,
+    pub operation: Expr,
+    pub divisor: u64,
+    pub if_true: usize,
+    pub if_false: usize,
+}
+
+impl Monkey {
+    /// Monkey an item with this worry level gets thrown to.
+    pub fn target(&self, worry: u64) -> usize {
+        if worry.is_multiple_of(self.divisor) {
+            self.if_true
+        } else {
+            self.if_false
+        }
+    }
+}
+
+/// The rest of the next line after `prefix`, ignoring indentation.
+fn field<'a, I: Iterator<Item = &'a str>>(lines: &mut I, prefix: &str) -> Result<&'a str, String> {
+    match lines.next() {
+        Some(line) => line.trim().strip_prefix(prefix).ok_or(format!(
+            "expected {:?}, found {:?}",
+            prefix,
+            line.trim()
+        )),
+        None => Err(format!("missing {:?}", prefix)),
+    }
+}
+
+fn parse_monkey(index: usize, lines: &[&str]) -> Result<Monkey, String> {
+    let number = |x: &str| {
+        x.trim()
+            .parse()
+            .map_err(|_| format!("not a number: {:?}", x))
+    };
+    let mut lines = lines.iter().copied();
+    if field(&mut lines, "Monkey ")? != format!("{}:", index) {
+        return Err("monkeys must be numbered in order".to_string());
+    }
+    let items = field(&mut lines, "Starting items:")?;
+    let items = if items.trim().is_empty() {
+        Vec::new()
+    } else {
+        items.split(',').map(number).collect::<Result<_, _>>()?
+    };
+    let operation = Expr::parse(field(&mut lines, "Operation: new =")?)?;
+    let divisor = number(field(&mut lines, "Test: divisible by")?)?;
+    if divisor == 0 {
+        return Err("cannot test divisibility by 0".to_string());
+    }
+    let if_true = number(field(&mut lines, "If true: throw to monkey")?)? as usize;
+    let if_false = number(field(&mut lines, "If false: throw to monkey")?)? as usize;
+    if let Some(line) = lines.next() {
+        return Err(format!("unexpected line: {:?}", line.trim()));
+    }
+    Ok(Monkey {
+        items,
+        operation,
+        divisor,
+        if_true,
+        if_false,
+    })
+}
+
+/// Monkeys described by blocks of lines separated by blank ones.
+pub fn parse_input(text: &str) -> Result<Vec<Monkey>, String> {
+    let mut blocks: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            blocks.push(Vec::new());
+        } else {
+            blocks.last_mut().unwrap().push(line);
+        }
+    }
+    blocks.retain(|x| !x.is_empty());
+    let mut monkeys = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        monkeys.push(parse_monkey(i, block).map_err(|e| format!("monkey {}: {}", i, e))?);
+    }
+    for (i, monkey) in monkeys.iter().enumerate() {
+        for target in [monkey.if_true, monkey.if_false] {
+            if target == i || target >= monkeys.len() {
+                return Err(format!("monkey {}: cannot throw to monkey {}", i, target));
+            }
+        }
+    }
+    Ok(monkeys)
+}
+
+/// Least common multiple of the divisors. Worry levels reduced modulo it are
+/// thrown exactly where the full ones would be, as long as the relief never
+/// divides them.
+pub fn common_modulus(monkeys: &[Monkey]) -> Result<u64, String> {
+    math::lcm_all(monkeys.iter().map(|x| x.divisor))
+        .ok_or_else(|| "Divisors have no common multiple within 64 bits".to_string())
+}
+
+fn overflow() -> String {
+    "Worry level overflowed".to_string()
+}
+
+/// Items each monkey inspects over `rounds` rounds, with `relief` applied to
+/// every worry level after the monkey's operation.
+pub fn simulate<F: Fn(u64) -> u64>(
+    monkeys: &[Monkey],
+    rounds: usize,
+    relief: F,
+) -> Result<Vec<usize>, String> {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|x| x.items.clone()).collect();
+    let mut inspected = vec![0; monkeys.len()];
+    for _ in 0..rounds {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            let held = std::mem::take(&mut items[i]);
+            inspected[i] += held.len();
+            for worry in held {
+                let worry = relief(monkey.operation.eval(worry).ok_or_else(overflow)?);
+                items[monkey.target(worry)].push(worry);
+            }
+        }
+    }
+    Ok(inspected)
+}
+
+/// Same as `simulate`, following one item at a time since no item's worry
+/// level depends on any other. An item thrown to a monkey earlier in the
+/// order waits for the next round, one thrown further along moves again in
+/// the same round.
+pub fn track_items<F: Fn(u64) -> u64>(
+    monkeys: &[Monkey],
+    rounds: usize,
+    relief: F,
+) -> Result<Vec<usize>, String> {
+    let mut inspected = vec![0; monkeys.len()];
+    for (start, monkey) in monkeys.iter().enumerate() {
+        for item in &monkey.items {
+            let (mut holder, mut worry, mut round) = (start, *item, 0);
+            while round < rounds {
+                let monkey = &monkeys[holder];
+                inspected[holder] += 1;
+                worry = relief(monkey.operation.eval(worry).ok_or_else(overflow)?);
+                let target = monkey.target(worry);
+                if target < holder {
+                    round += 1;
+                }
+                holder = target;
+            }
+        }
+    }
+    Ok(inspected)
+}
+
+/// Product of the two highest inspection counts.
+pub fn monkey_business(inspected: &[usize]) -> usize {
+    let mut counts = inspected.to_vec();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts.iter().take(2).product()
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 11, 'a', "rounds", |text| {
+            let inspected = simulate(&parse_input(text)?, 20, |x| x / 3)?;
+            Ok(monkey_business(&inspected).to_string())
+        }),
+        Solution::new(2022, 11, 'a', "items", |text| {
+            let inspected = track_items(&parse_input(text)?, 20, |x| x / 3)?;
+            Ok(monkey_business(&inspected).to_string())
+        }),
+        Solution::new(2022, 11, 'b', "rounds", |text| {
+            let monkeys = parse_input(text)?;
+            let modulus = common_modulus(&monkeys)?;
+            let inspected = simulate(&monkeys, 10000, |x| x % modulus)?;
+            Ok(monkey_business(&inspected).to_string())
+        }),
+        Solution::new(2022, 11, 'b', "items", |text| {
+            let monkeys = parse_input(text)?;
+            let modulus = common_modulus(&monkeys)?;
+            let inspected = track_items(&monkeys, 10000, |x| x % modulus)?;
+            Ok(monkey_business(&inspected).to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{generate, prop};
+
+    /// Notes for `count` monkeys, each throwing to two others, so at least
+    /// two are needed.
+    fn monkeys(rng: &mut Rng, count: usize) -> Vec<String> {
+        assert!(count >= 2, "Monkeys need another monkey to throw to");
+        let mut lines = Vec::new();
+        for i in 0..count {
+            let items: Vec<String> = (0..rng.below(5))
+                .map(|_| (1 + rng.below(99)).to_string())
+                .collect();
+            let operation = match rng.below(3) {
+                0 => "old * old".to_string(),
+                1 => format!("old + {}", 1 + rng.below(9)),
+                _ => format!("old * {}", 2 + rng.below(18)),
+            };
+            let divisor = [2, 3, 5, 7, 11, 13, 17, 19, 23][rng.below(9) as usize];
+            let mut target = || (i + 1 + rng.below(count as u64 - 1) as usize) % count;
+            let (if_true, if_false) = (target(), target());
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.push(format!("Monkey {}:", i));
+            lines.push(format!("  Starting items: {}", items.join(", ")));
+            lines.push(format!("  Operation: new = {}", operation));
+            lines.push(format!("  Test: divisible by {}", divisor));
+            lines.push(format!("    If true: throw to monkey {}", if_true));
+            lines.push(format!("    If false: throw to monkey {}", if_false));
+        }
+        lines
+    }
+
+    const EXAMPLE: &str = "
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn day11_example1() {
+        let monkeys = parse_input(EXAMPLE).unwrap();
+        assert_eq!(4, monkeys.len());
+        assert_eq!(vec![54, 65, 75, 74], monkeys[1].items);
+        assert_eq!("old * old", monkeys[2].operation.to_string());
+        assert_eq!(Ok(96577), common_modulus(&monkeys));
+        let inspected = simulate(&monkeys, 20, |x| x / 3).unwrap();
+        assert_eq!(vec![101, 95, 7, 105], inspected);
+        assert_eq!(10605, monkey_business(&inspected));
+        for solution in solutions().iter().filter(|x| x.part == 'a') {
+            assert_eq!(Ok("10605".to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day11_example2() {
+        let monkeys = parse_input(EXAMPLE).unwrap();
+        let modulus = common_modulus(&monkeys).unwrap();
+        assert_eq!(
+            Ok(vec![52166, 47830, 1938, 52013]),
+            simulate(&monkeys, 10000, |x| x % modulus)
+        );
+        assert_eq!(
+            Ok(vec![5204, 4792, 199, 5192]),
+            track_items(&monkeys, 1000, |x| x % modulus)
+        );
+        for solution in solutions().iter().filter(|x| x.part == 'b') {
+            assert_eq!(Ok("2713310158".to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day11_expressions_and_errors() {
+        let expr = Expr::parse("old + 2 * old * 3 + 1").unwrap();
+        assert_eq!(Some(5 + 2 * 5 * 3 + 1), expr.eval(5));
+        assert_eq!("old + 2 * old * 3 + 1", expr.to_string());
+        let product = Expr::Mul(Box::new(expr), Box::new(Expr::Old));
+        assert_eq!("(old + 2 * old * 3 + 1) * old", product.to_string());
+        assert_eq!(None, Expr::parse("old * old").unwrap().eval(1 << 32));
+        assert!(Expr::parse("old * * 2").is_err());
+        assert!(Expr::parse("").is_err());
+
+        let wrong_target = EXAMPLE.replace("throw to monkey 3", "throw to monkey 7");
+        assert_eq!(
+            Err("monkey 0: cannot throw to monkey 7".to_string()),
+            parse_input(&wrong_target)
+        );
+        let zero = EXAMPLE.replace("by 19", "by 0");
+        assert_eq!(
+            Err("monkey 1: cannot test divisibility by 0".to_string()),
+            parse_input(&zero)
+        );
+        let missing = EXAMPLE.replace("  Operation: new = old + 3\n", "");
+        assert_eq!(
+            Err(
+                "monkey 3: expected \"Operation: new =\", found \"Test: divisible by 17\""
+                    .to_string()
+            ),
+            parse_input(&missing)
+        );
+        let squaring = "Monkey 0:\n  Starting items: 10\n  Operation: new = old * old\n  \
+                        Test: divisible by 2\n    If true: throw to monkey 1\n    \
+                        If false: throw to monkey 1\n\nMonkey 1:\n  Starting items:\n  \
+                        Operation: new = old\n  Test: divisible by 2\n    \
+                        If true: throw to monkey 0\n    If false: throw to monkey 0\n";
+        let monkeys = parse_input(squaring).unwrap();
+        assert_eq!(Err(overflow()), simulate(&monkeys, 10, |x| x));
+        assert_eq!(Err(overflow()), track_items(&monkeys, 10, |x| x));
+        let huge = EXAMPLE
+            .replace("by 23", "by 4294967291")
+            .replace("by 19", "by 4294967279");
+        let monkeys = parse_input(&huge).unwrap();
+        let error = "Divisors have no common multiple within 64 bits".to_string();
+        assert_eq!(Err(error.clone()), common_modulus(&monkeys));
+        for solution in solutions().iter().filter(|x| x.part == 'b') {
+            assert_eq!(Err(error.clone()), (solution.run)(&huge));
+        }
+    }
+
+    #[test]
+    fn day11_matches_items() {
+        prop::check(
+            11,
+            300,
+            |rng| {
+                let count = 2 + rng.below(6) as usize;
+                let rounds = rng.below(100) as usize;
+                (monkeys(rng, count), rounds)
+            },
+            |(lines, rounds)| {
+                let monkeys = match parse_input(&generate::lines_input(lines)) {
+                    Ok(monkeys) => monkeys,
+                    Err(_) => return Ok(()),
+                };
+                let modulus = common_modulus(&monkeys)?;
+                prop::expect_eq(
+                    track_items(&monkeys, *rounds, |x| x % modulus),
+                    simulate(&monkeys, *rounds, |x| x % modulus),
+                )?;
+                prop::expect_eq(
+                    track_items(&monkeys, *rounds, |x| x / 3),
+                    simulate(&monkeys, *rounds, |x| x / 3),
+                )
+            },
+        );
+    }
+}
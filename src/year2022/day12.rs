@@ -0,0 +1,325 @@
+//! Hill climbing algorithm: the fewest steps up a height map when each step
+//! may climb at most one level.
+
+use std::collections::HashMap;
+
+use crate::search::{self, Search};
+use crate::solution::Solution;
+use crate::{Direction, Grid};
+
+pub type Position = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeightMap {
+    /// Elevation of each square, 0 for `a` up to 25 for `z`.
+    pub heights: Grid<u8>,
+    pub start: Position,
+    pub end: Position,
+}
+
+pub fn parse_input(text: &str) -> Result<HeightMap, String> {
+    let letters = Grid::parse(text.trim(), |c| match c {
+        'a'..='z' | 'S' | 'E' => Ok(c),
+        _ => Err(format!("not a height: {:?}", c)),
+    })?;
+    let find = |marker: char| {
+        let mut found = letters.positions().filter(|x| letters[*x] == marker);
+        match (found.next(), found.next()) {
+            (Some(position), None) => Ok(position),
+            (None, _) => Err(format!("No {:?} on the map", marker)),
+            (Some(_), Some(_)) => Err(format!("More than one {:?} on the map", marker)),
+        }
+    };
+    let (start, end) = (find('S')?, find('E')?);
+    let heights = letters.map(|x| match x {
+        'S' => 0,
+        'E' => 25,
+        _ => (*x as u8) - b'a',
+    });
+    Ok(HeightMap {
+        heights,
+        start,
+        end,
+    })
+}
+
+impl HeightMap {
+    /// Squares next to `position` that can be stepped onto from it.
+    pub fn uphill(&self, position: &Position) -> Vec<Position> {
+        let limit = self.heights[*position] + 1;
+        Direction::ALL
+            .iter()
+            .filter_map(|x| self.heights.step(*position, *x))
+            .filter(|x| self.heights[*x] <= limit)
+            .collect()
+    }
+
+    /// Squares next to `position` that can step onto it, for searching from
+    /// the end backward.
+    pub fn downhill(&self, position: &Position) -> Vec<Position> {
+        let height = self.heights[*position];
+        Direction::ALL
+            .iter()
+            .filter_map(|x| self.heights.step(*position, *x))
+            .filter(|x| height <= self.heights[*x] + 1)
+            .collect()
+    }
+
+    /// Steps still needed from `position`: at least its distance to the end and
+    /// the height left to climb.
+    fn lower_bound(&self, position: &Position) -> u64 {
+        let distance = position.0.abs_diff(self.end.0) + position.1.abs_diff(self.end.1);
+        let climb = self.heights[self.end].saturating_sub(self.heights[*position]);
+        distance.max(climb as usize) as u64
+    }
+
+    /// Squares at the lowest elevation, where part two may start.
+    pub fn lowest(&self) -> Vec<Position> {
+        self.heights
+            .positions()
+            .filter(|x| self.heights[*x] == 0)
+            .collect()
+    }
+
+    /// The map with `path` drawn over it as in the puzzle, an arrow on each
+    /// square toward the next one and `E` on the end, leaving the elevation
+    /// letters showing everywhere else.
+    pub fn render_path(&self, path: &[Position]) -> String {
+        let mut marks: HashMap<Position, char> = HashMap::new();
+        for pair in path.windows(2) {
+            let direction = Direction::ALL
+                .iter()
+                .find(|x| self.heights.step(pair[0], **x) == Some(pair[1]));
+            let arrow = match direction {
+                Some(Direction::Up) => '^',
+                Some(Direction::Down) => 'v',
+                Some(Direction::Left) => '<',
+                Some(Direction::Right) => '>',
+                None => '?',
+            };
+            marks.insert(pair[0], arrow);
+        }
+        let mut text = String::new();
+        for y in 0..self.heights.height() {
+            for x in 0..self.heights.width() {
+                let letter = if (x, y) == self.end {
+                    'E'
+                } else if (x, y) == self.start {
+                    'S'
+                } else {
+                    (b'a' + self.heights[(x, y)]) as char
+                };
+                text.push(*marks.get(&(x, y)).unwrap_or(&letter));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Breadth-first search up from `starts` to the end.
+pub fn climb(map: &HeightMap, starts: &[Position]) -> Search<Position> {
+    search::bfs(starts.iter().copied(), |x| map.uphill(x), |x| *x == map.end)
+}
+
+/// Same as `climb` with A*, guided by how far and how high the end still is.
+pub fn guided_climb(map: &HeightMap, starts: &[Position]) -> Search<Position> {
+    search::astar(
+        starts.iter().copied(),
+        |x| map.uphill(x).into_iter().map(|x| (x, 1)),
+        |x| map.lower_bound(x),
+        |x| *x == map.end,
+    )
+}
+
+/// Searches down from the end to the nearest lowest square, then turns the
+/// path around so it climbs.
+pub fn descend(map: &HeightMap) -> Option<Vec<Position>> {
+    let search = search::bfs([map.end], |x| map.downhill(x), |x| map.heights[*x] == 0);
+    let mut path = search.goal_path()?;
+    path.reverse();
+    Some(path)
+}
+
+/// Fewest steps of a path, which holds its start as well.
+fn steps(path: Option<Vec<Position>>) -> Result<String, String> {
+    match path {
+        Some(path) => Ok((path.len() - 1).to_string()),
+        None => Err("No way up to the end".to_string()),
+    }
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 12, 'a', "bfs", |text| {
+            let map = parse_input(text)?;
+            steps(climb(&map, &[map.start]).goal_path())
+        }),
+        Solution::new(2022, 12, 'a', "astar", |text| {
+            let map = parse_input(text)?;
+            steps(guided_climb(&map, &[map.start]).goal_path())
+        }),
+        Solution::new(2022, 12, 'b', "bfs", |text| {
+            let map = parse_input(text)?;
+            steps(climb(&map, &map.lowest()).goal_path())
+        }),
+        Solution::new(2022, 12, 'b', "reverse", |text| {
+            steps(descend(&parse_input(text)?))
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{generate, prop};
+
+    /// `height` rows of `width` elevation letters rising from the top left
+    /// corner toward the bottom right, with `S` and `E` on two different
+    /// squares of it.
+    fn height_map(rng: &mut Rng, width: usize, height: usize) -> Vec<String> {
+        assert!(width * height >= 2, "S and E need two squares");
+        let mut rows: Vec<Vec<u8>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let slope = 26 * (x + y) / (width + height);
+                        b'a' + (slope as u8 + rng.below(4) as u8).min(25)
+                    })
+                    .collect()
+            })
+            .collect();
+        let cells = (width * height) as u64;
+        let start = rng.below(cells) as usize;
+        let end = (start + 1 + rng.below(cells - 1) as usize) % (width * height);
+        rows[start / width][start % width] = b'S';
+        rows[end / width][end % width] = b'E';
+        rows.into_iter()
+            .map(|x| String::from_utf8(x).unwrap())
+            .collect()
+    }
+
+    const EXAMPLE: &str = "
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    /// Whether `path` goes one allowed step at a time from `start` to the end.
+    fn is_climb(map: &HeightMap, path: &[Position], start: Option<Position>) -> bool {
+        path.windows(2).all(|x| map.uphill(&x[0]).contains(&x[1]))
+            && path.last() == Some(&map.end)
+            && start.is_none_or(|x| path.first() == Some(&x))
+    }
+
+    #[test]
+    fn day12_example1() {
+        let map = parse_input(EXAMPLE).unwrap();
+        assert_eq!(((0, 0), (5, 2)), (map.start, map.end));
+        assert_eq!(vec![(0, 1), (1, 0)], map.uphill(&(0, 0)));
+        let path = climb(&map, &[map.start]).goal_path().unwrap();
+        assert_eq!(32, path.len());
+        assert!(is_climb(&map, &path, Some(map.start)));
+        // The path shown in the puzzle, one of several as short.
+        let diagram = "\
+v..v<<<<
+>v.vv<<^
+.>vv>E^^
+..v>>>^^
+..>>>>>^
+";
+        let arrows = Grid::parse(diagram.trim(), Ok).unwrap();
+        let mut shown = vec![map.start];
+        while let Some(direction) = match arrows[*shown.last().unwrap()] {
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        } {
+            let next = arrows.step(*shown.last().unwrap(), direction).unwrap();
+            shown.push(next);
+        }
+        assert_eq!(32, shown.len());
+        assert!(is_climb(&map, &shown, Some(map.start)));
+        let expected = "\
+vabv<<<<
+>vcvv<<^
+a>vv>E^^
+acv>>>^^
+ab>>>>>^
+";
+        assert_eq!(expected, map.render_path(&shown));
+        assert_eq!(EXAMPLE.trim_start(), map.render_path(&[]));
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "31" } else { "29" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day12_example2() {
+        let map = parse_input(EXAMPLE).unwrap();
+        let path = descend(&map).unwrap();
+        assert_eq!(30, path.len());
+        assert!(is_climb(&map, &path, None));
+        assert_eq!(0, map.heights[path[0]]);
+        assert_eq!(Some(29), climb(&map, &map.lowest()).goal_cost());
+
+        let cliff = parse_input("Sbc\nxyE").unwrap();
+        assert_eq!(None, climb(&cliff, &[cliff.start]).goal);
+        assert_eq!(
+            Err("No way up to the end".to_string()),
+            (solutions()[0].run)("Sbc\nxyE")
+        );
+        assert_eq!(Err("No 'E' on the map".to_string()), parse_input("Sab"));
+        assert_eq!(
+            Err("More than one 'S' on the map".to_string()),
+            parse_input("SaS\naaE")
+        );
+        assert_eq!(
+            Err("line 2: not a height: '1'".to_string()),
+            parse_input("Sab\na1E")
+        );
+    }
+
+    #[test]
+    fn day12_matches_astar() {
+        prop::check(
+            12,
+            500,
+            |rng| {
+                let width = 2 + rng.below(10) as usize;
+                let height = 1 + rng.below(10) as usize;
+                height_map(rng, width, height)
+            },
+            |rows| {
+                let map = match parse_input(&generate::lines_input(rows)) {
+                    Ok(map) => map,
+                    Err(_) => return Ok(()),
+                };
+                let bfs = climb(&map, &[map.start]);
+                prop::expect_eq(
+                    bfs.goal_cost(),
+                    guided_climb(&map, &[map.start]).goal_cost(),
+                )?;
+                if let Some(path) = bfs.goal_path() {
+                    prop::expect_eq(true, is_climb(&map, &path, Some(map.start)))?;
+                }
+                let lowest = climb(&map, &map.lowest()).goal_path();
+                let reversed = descend(&map);
+                prop::expect_eq(
+                    lowest.as_ref().map(|x| x.len()),
+                    reversed.as_ref().map(|x| x.len()),
+                )?;
+                if let Some(path) = reversed {
+                    prop::expect_eq(true, is_climb(&map, &path, None))?;
+                }
+                Ok(())
+            },
+        );
+    }
+}
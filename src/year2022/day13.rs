@@ -0,0 +1,518 @@
+//! Distress signal: ordering packets of nested integer lists.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::solution::Solution;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Int(u32),
+    List(Vec<Packet>),
+}
+
+impl Ord for Packet {
+    /// Integers compare by value and lists element by element, the shorter
+    /// one first when all shared elements tie. An integer compared with a
+    /// list counts as a list holding just that integer.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+            (Packet::Int(a), Packet::List(b)) => [Packet::Int(*a)][..].cmp(b),
+            (Packet::List(a), Packet::Int(b)) => a[..].cmp(&[Packet::Int(*b)]),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Int(value) => write!(f, "{}", value),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Deepest nesting of lists a packet may have. Parsing, comparing and
+/// dropping packets all recurse once per level.
+pub const MAX_DEPTH: usize = 1000;
+
+/// Recursive descent over the bytes of one packet.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Lists open at the current byte.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Error at the current byte, with columns counted from 1.
+    fn error(&self, expected: &str) -> String {
+        match self.bytes.get(self.position) {
+            Some(byte) => format!(
+                "column {}: expected {}, found {:?}",
+                self.position + 1,
+                expected,
+                *byte as char
+            ),
+            None => format!("column {}: expected {}", self.position + 1, expected),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn packet(&mut self) -> Result<Packet, String> {
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(b'0'..=b'9') => self.int(),
+            _ => Err(self.error("'[' or a digit")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!(
+                "column {}: lists nested more than {} deep",
+                self.position + 1,
+                MAX_DEPTH
+            ));
+        }
+        self.position += 1;
+        self.depth += 1;
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            self.depth -= 1;
+            return Ok(Packet::List(items));
+        }
+        loop {
+            items.push(self.packet()?);
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    self.depth -= 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return Err(self.error("',' or ']'")),
+            }
+        }
+    }
+
+    fn int(&mut self) -> Result<Packet, String> {
+        let start = self.position;
+        while self.peek().is_some_and(|x| x.is_ascii_digit()) {
+            self.position += 1;
+        }
+        let digits = std::str::from_utf8(&self.bytes[start..self.position]).unwrap();
+        digits
+            .parse()
+            .map(Packet::Int)
+            .map_err(|_| format!("column {}: {} is too large", start + 1, digits))
+    }
+}
+
+impl FromStr for Packet {
+    type Err = String;
+
+    /// Parses one packet, reporting the column of the first mistake.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: s.as_bytes(),
+            position: 0,
+            depth: 0,
+        };
+        let packet = parser.packet()?;
+        if parser.position < s.len() {
+            return Err(parser.error("the end of the packet"));
+        }
+        Ok(packet)
+    }
+}
+
+/// Every packet in order, skipping the blank lines between pairs.
+pub fn parse_packets(text: &str) -> Result<Vec<Packet>, String> {
+    let mut packets = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if !line.is_empty() {
+            packets.push(line.parse().map_err(|e| format!("line {}, {}", i + 1, e))?);
+        }
+    }
+    Ok(packets)
+}
+
+pub fn parse_input(text: &str) -> Result<Vec<(Packet, Packet)>, String> {
+    let packets = parse_packets(text)?;
+    if !packets.len().is_multiple_of(2) {
+        return Err("The last packet has no pair".to_string());
+    }
+    Ok(packets
+        .chunks(2)
+        .map(|x| (x[0].clone(), x[1].clone()))
+        .collect())
+}
+
+/// Sum of the 1-based indices of the pairs already in the right order.
+pub fn ordered_pairs(pairs: &[(Packet, Packet)]) -> usize {
+    pairs
+        .iter()
+        .zip(1..)
+        .filter(|((left, right), _)| left < right)
+        .map(|(_, index)| index)
+        .sum()
+}
+
+/// The `[[2]]` and `[[6]]` packets added for part two, in order.
+pub fn dividers() -> [Packet; 2] {
+    let divider = |x| Packet::List(vec![Packet::List(vec![Packet::Int(x)])]);
+    [divider(2), divider(6)]
+}
+
+/// Product of the dividers' 1-based positions once every packet is sorted,
+/// each divider after any packet comparing equal to it. Only how many packets
+/// sort before each divider matters, so the packets are counted rather than
+/// sorted, and the second divider also comes after the first.
+pub fn decoder_key(packets: &[Packet]) -> usize {
+    let [first, second] = dividers();
+    let before_first = packets.iter().filter(|x| **x <= first).count();
+    let before_second = packets.iter().filter(|x| **x <= second).count();
+    (before_first + 1) * (before_second + 2)
+}
+
+/// Same as `decoder_key`, sorting the packets with the dividers.
+pub fn sorted_decoder_key(packets: &[Packet]) -> usize {
+    let mut all: Vec<(Packet, bool)> = packets.iter().map(|x| (x.clone(), false)).collect();
+    all.extend(dividers().map(|x| (x, true)));
+    all.sort();
+    all.iter()
+        .zip(1..)
+        .filter(|((_, divider), _)| *divider)
+        .map(|(_, position)| position)
+        .product()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Int(u32),
+}
+
+/// Tokens of a packet's text, last one first so they pop off in order.
+fn tokens(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut digits = String::new();
+    let mut depth = 0;
+    for (i, c) in text.chars().chain([',']).enumerate() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        if !digits.is_empty() {
+            let value = digits
+                .parse()
+                .map_err(|_| format!("{} is too large", digits))?;
+            tokens.push(Token::Int(value));
+            digits.clear();
+        }
+        match c {
+            '[' if depth == MAX_DEPTH => {
+                return Err(format!(
+                    "column {}: lists nested more than {} deep",
+                    i + 1,
+                    MAX_DEPTH
+                ))
+            }
+            '[' => {
+                depth += 1;
+                tokens.push(Token::Open);
+            }
+            ']' => {
+                depth = depth.saturating_sub(1);
+                tokens.push(Token::Close);
+            }
+            ',' => {}
+            _ => return Err(format!("not a packet: {:?}", text)),
+        }
+    }
+    tokens.reverse();
+    Ok(tokens)
+}
+
+/// Compares two packets straight from their text without building either.
+/// When an integer meets an opening bracket, the integer is put back as the
+/// only item of a list whose opening bracket was just matched.
+pub fn compare_text(left: &str, right: &str) -> Result<Ordering, String> {
+    let (mut left, mut right) = (tokens(left)?, tokens(right)?);
+    loop {
+        match (left.pop(), right.pop()) {
+            (None, None) => return Ok(Ordering::Equal),
+            (None, Some(_)) => return Ok(Ordering::Less),
+            (Some(_), None) => return Ok(Ordering::Greater),
+            (Some(Token::Open), Some(Token::Open)) | (Some(Token::Close), Some(Token::Close)) => {}
+            (Some(Token::Int(a)), Some(Token::Int(b))) if a == b => {}
+            (Some(Token::Int(a)), Some(Token::Int(b))) => return Ok(a.cmp(&b)),
+            (Some(Token::Close), _) => return Ok(Ordering::Less),
+            (_, Some(Token::Close)) => return Ok(Ordering::Greater),
+            (Some(Token::Int(a)), Some(Token::Open)) => left.extend([Token::Close, Token::Int(a)]),
+            (Some(Token::Open), Some(Token::Int(b))) => right.extend([Token::Close, Token::Int(b)]),
+        }
+    }
+}
+
+/// Same as `ordered_pairs`, comparing each pair's text with `compare_text`.
+pub fn ordered_text_pairs(text: &str) -> Result<usize, String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .collect();
+    if !lines.len().is_multiple_of(2) {
+        return Err("The last packet has no pair".to_string());
+    }
+    let mut total = 0;
+    for (pair, index) in lines.chunks(2).zip(1..) {
+        if compare_text(pair[0], pair[1])? == Ordering::Less {
+            total += index;
+        }
+    }
+    Ok(total)
+}
+
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 13, 'a', "tree", |text| {
+            Ok(ordered_pairs(&parse_input(text)?).to_string())
+        }),
+        Solution::new(2022, 13, 'a', "tokens", |text| {
+            ordered_text_pairs(text).map(|x| x.to_string())
+        }),
+        Solution::new(2022, 13, 'b', "count", |text| {
+            Ok(decoder_key(&parse_packets(text)?).to_string())
+        }),
+        Solution::new(2022, 13, 'b', "sort", |text| {
+            Ok(sorted_decoder_key(&parse_packets(text)?).to_string())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::{generate, prop};
+
+    /// A packet of small integers and lists nested up to `depth` deep.
+    fn packet(rng: &mut Rng, depth: usize) -> String {
+        if depth > 0 && rng.below(3) > 0 {
+            let items: Vec<String> = (0..rng.below(4)).map(|_| packet(rng, depth - 1)).collect();
+            format!("[{}]", items.join(","))
+        } else {
+            rng.below(5).to_string()
+        }
+    }
+
+    /// `count` pairs of list packets, a blank line after each pair but
+    /// the last.
+    fn packet_pairs(rng: &mut Rng, count: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for i in 0..count {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            for _ in 0..2 {
+                let items: Vec<String> = (0..rng.below(4)).map(|_| packet(rng, 3)).collect();
+                lines.push(format!("[{}]", items.join(",")));
+            }
+        }
+        lines
+    }
+
+    const EXAMPLE: &str = "
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    #[test]
+    fn day13_example1() {
+        let pairs = parse_input(EXAMPLE).unwrap();
+        let ordered: Vec<bool> = pairs.iter().map(|(a, b)| a < b).collect();
+        assert_eq!(
+            vec![true, true, false, true, false, true, false, false],
+            ordered
+        );
+        assert_eq!(13, ordered_pairs(&pairs));
+        assert_eq!("[[1],[2,3,4]]", pairs[1].0.to_string());
+        assert_eq!(Ok(Ordering::Less), compare_text("[[1],[2,3,4]]", "[[1],4]"));
+        assert_eq!(Ok(Ordering::Equal), compare_text("[[2]]", "[2]"));
+        for solution in solutions() {
+            let expected = if solution.part == 'a' { "13" } else { "140" };
+            assert_eq!(Ok(expected.to_string()), (solution.run)(EXAMPLE));
+        }
+    }
+
+    #[test]
+    fn day13_example2() {
+        let mut packets = parse_packets(EXAMPLE).unwrap();
+        assert_eq!(140, decoder_key(&packets));
+        // A packet equal to a divider sorts just before it.
+        let mut doubled = packets.clone();
+        doubled.push("[2]".parse().unwrap());
+        assert_eq!(11 * 15, decoder_key(&doubled));
+        assert_eq!(11 * 15, sorted_decoder_key(&doubled));
+        packets.extend(dividers());
+        packets.sort();
+        let sorted: Vec<String> = packets.iter().map(|x| x.to_string()).collect();
+        assert_eq!(
+            vec![
+                "[]",
+                "[[]]",
+                "[[[]]]",
+                "[1,1,3,1,1]",
+                "[1,1,5,1,1]",
+                "[[1],[2,3,4]]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[[1],4]",
+                "[[2]]",
+                "[3]",
+                "[[4,4],4,4]",
+                "[[4,4],4,4,4]",
+                "[[6]]",
+                "[7,7,7]",
+                "[7,7,7,7]",
+                "[[8,7,6]]",
+                "[9]",
+            ],
+            sorted
+        );
+    }
+
+    #[test]
+    fn day13_parse_errors() {
+        assert_eq!(Ok(Packet::List(Vec::new())), "[]".parse());
+        assert_eq!(
+            Err("column 5: expected ',' or ']', found ' '".to_string()),
+            "[1,2 ]".parse::<Packet>()
+        );
+        assert_eq!(
+            Err("column 4: expected '[' or a digit".to_string()),
+            "[1,".parse::<Packet>()
+        );
+        assert_eq!(
+            Err("column 4: expected ',' or ']'".to_string()),
+            "[[1".parse::<Packet>()
+        );
+        assert_eq!(
+            Err("column 3: expected the end of the packet, found ']'".to_string()),
+            "[]]".parse::<Packet>()
+        );
+        assert_eq!(
+            Err("column 2: 99999999999 is too large".to_string()),
+            "[99999999999]".parse::<Packet>()
+        );
+        assert_eq!(
+            Err("line 3, column 1: expected '[' or a digit, found 'x'".to_string()),
+            parse_packets("[1]\n\nx")
+        );
+        assert_eq!(
+            Err("The last packet has no pair".to_string()),
+            parse_input("[1]\n[2]\n\n[3]")
+        );
+    }
+
+    #[test]
+    fn day13_deep_nesting() {
+        let nested = |depth: usize| format!("{}7{}", "[".repeat(depth), "]".repeat(depth));
+        let deepest: Packet = nested(MAX_DEPTH).parse().unwrap();
+        let shallower: Packet = nested(MAX_DEPTH - 1).parse().unwrap();
+        assert_eq!(Ordering::Equal, deepest.cmp(&shallower));
+        assert_eq!(
+            Ok(Ordering::Equal),
+            compare_text(&nested(MAX_DEPTH), &nested(MAX_DEPTH - 1))
+        );
+        let error = format!(
+            "column {}: lists nested more than {} deep",
+            MAX_DEPTH + 1,
+            MAX_DEPTH
+        );
+        assert_eq!(Err(error.clone()), nested(100_000).parse::<Packet>());
+        assert_eq!(Err(error.clone()), compare_text(&nested(100_000), "[]"));
+        let pair = format!("{}\n[]", nested(100_000));
+        for solution in solutions().iter().filter(|x| x.part == 'a') {
+            assert!((solution.run)(&pair).unwrap_err().ends_with(&error));
+        }
+    }
+
+    #[test]
+    fn day13_matches_tokens() {
+        prop::check(
+            13,
+            1000,
+            |rng| {
+                let count = 1 + rng.below(6) as usize;
+                packet_pairs(rng, count)
+            },
+            |lines| {
+                let text = generate::lines_input(lines);
+                let packets = match parse_packets(&text) {
+                    Ok(packets) => packets,
+                    Err(_) => return Ok(()),
+                };
+                for (packet, line) in packets.iter().zip(lines.iter().filter(|x| !x.is_empty())) {
+                    prop::expect_eq(line.clone(), packet.to_string())?;
+                }
+                for pair in packets.chunks(2) {
+                    let expected = pair[0].cmp(&pair[1]);
+                    prop::expect_eq(
+                        Ok(expected),
+                        compare_text(&pair[0].to_string(), &pair[1].to_string()),
+                    )?;
+                    prop::expect_eq(expected.reverse(), pair[1].cmp(&pair[0]))?;
+                }
+                prop::expect_eq(sorted_decoder_key(&packets), decoder_key(&packets))
+            },
+        );
+    }
+}
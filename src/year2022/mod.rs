@@ -0,0 +1,34 @@
+use crate::solution::Solution;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+
+pub fn solutions() -> Vec<Solution> {
+    [
+        day01::solutions(),
+        day02::solutions(),
+        day03::solutions(),
+        day04::solutions(),
+        day05::solutions(),
+        day06::solutions(),
+        day07::solutions(),
+        day08::solutions(),
+        day09::solutions(),
+        day10::solutions(),
+        day11::solutions(),
+        day12::solutions(),
+        day13::solutions(),
+    ]
+    .concat()
+}